@@ -1,13 +1,14 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Encode, Decode};
-use frame_support::{decl_module, decl_storage, decl_error, ensure, StorageValue, StorageMap, traits::{Randomness, Currency, ExistenceRequirement}, Parameter};
+use frame_support::{decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, traits::{Randomness, Currency, ExistenceRequirement, Get}, weights::Weight, Parameter};
 use sp_io::hashing::blake2_128;
 use frame_system::ensure_signed;
 use sp_runtime::{
-    traits::{AtLeast32Bit, Bounded, Member},
+    traits::{AtLeast32Bit, Bounded, Member, Saturating},
     DispatchError,
-    DispatchResult
+    DispatchResult,
+    RuntimeDebug,
 };
 
 // type KittyIndex = u32;
@@ -21,8 +22,23 @@ pub struct KittyLinkedItem<T: Trait> {
     pub next: Option<T::KittyIndex>,
 }
 
+/// Layouts of this pallet's storage, in the order they were released.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+    /// `KittiesCount` holds the id of the last created kitty.
+    V1_0_0,
+    /// `KittiesCount` holds the id of the next kitty.
+    V2_0_0,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
+}
+
 pub trait Trait: frame_system::Trait {
-    // type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type Randomness: Randomness<Self::Hash>;
     type KittyIndex: Parameter + Member + AtLeast32Bit + Bounded + Default + Copy;
     type Currency: Currency<Self::AccountId>;
@@ -37,6 +53,10 @@ decl_storage! {
         pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
         pub OwnedKitties get(fn owned_kitties): map hasher(blake2_128_concat) (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
         pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+
+        /// Storage layout in use. Chains that predate it are on `V1_0_0`, new
+        /// chains start on the latest layout and have nothing to migrate.
+        pub StorageVersion get(fn storage_version) build(|_| Releases::V2_0_0): Releases;
 	}
 }
 
@@ -52,17 +72,27 @@ decl_error! {
 	}
 }
 
-// decl_event! {
-    // pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
-        // Created(AccountId, T::KittyIndex),
-        // Transferred(AccountId, AccountId, T::KittyIndex),
-	// }
-// }
+decl_event! {
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+        <T as Trait>::KittyIndex,
+        Balance = BalanceOf<T>,
+    {
+        /// A kitty is created. (owner, kitty_id)
+        Created(AccountId, KittyIndex),
+        /// A kitty is transferred. (from, to, kitty_id)
+        Transferred(AccountId, AccountId, KittyIndex),
+        /// A kitty is available for sale. (owner, kitty_id, price)
+        Ask(AccountId, KittyIndex, Option<Balance>),
+        /// A kitty is sold. (from, to, kitty_id, price)
+        Sold(AccountId, AccountId, KittyIndex, Balance),
+    }
+}
 
 decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
         type Error = Error<T>;
-        // fn deposit_event() = default;
+        fn deposit_event() = default;
 
         #[weight = 0]
         pub fn create(origin) {
@@ -71,36 +101,40 @@ decl_module! {
             let dna = Self::random_value(&sender);
             let kitty = Kitty(dna);
             Self::insert_kitty(&sender, kitty_id, kitty);
-            // Self::deposit_event(RawEvent::Created(sender, kitty_id));
+            Self::deposit_event(RawEvent::Created(sender, kitty_id));
         }
 
         #[weight = 0]
         pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
             let sender = ensure_signed(origin)?;
             Self::do_transfer(&sender, &to, kitty_id)?;
-            // <KittyOwners<T>>::insert(kitty_id, to.clone());
-            // Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
+            Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
         }
 
         #[weight = 0]
         pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
             let sender = ensure_signed(origin)?;
-            Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
-            // let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
-            // Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+            let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
+            Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
         }
 
         fn on_runtime_upgrade() -> Weight {
-            let _ = Test::translate::<_, _>(|value: Option<u16> | value.map(|v| v as u32));
+            let mut weight = T::DbWeight::get().reads(1);
+            if Self::storage_version() == Releases::V1_0_0 {
+                weight = weight.saturating_add(Self::migrate_to_v2());
+            }
+            weight
         }
 
         #[weight = 0]
         pub fn ask(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) {
             let sender = ensure_signed(origin)?;
 
-            ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+            ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::RequireOwner);
 
 			<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
+
+            Self::deposit_event(RawEvent::Ask(sender, kitty_id, new_price));
         }
 
         #[weight = 0]
@@ -115,8 +149,9 @@ decl_module! {
 
 			T::Currency::transfer(&sender, &owner, kitty_price, ExistenceRequirement::KeepAlive)?;
 
-            <KittyPrices<T>>::remove(kitty_id);
-            <KittyOwners<T>>::insert(kitty_id, sender.clone());
+            Self::set_owner(kitty_id, &sender);
+
+            Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price));
         }
     }
 }
@@ -193,10 +228,36 @@ impl<T: Trait> OwnedKitties<T> {
 impl<T: Trait> Module<T> {
     // type Error = Error<T>;
     // fn deposit_event() = default;
+
+    /// `KittiesCount` used to hold the id of the last created kitty, so the next
+    /// `create` overwrote it. Move it past that kitty. Returns the weight consumed.
+    fn migrate_to_v2() -> Weight {
+        let last_id = Self::kitties_count();
+        let mut writes = 1;
+        if Kitties::<T>::contains_key(last_id) {
+            KittiesCount::<T>::put(last_id.saturating_add(1u32.into()));
+            writes += 1;
+        }
+        StorageVersion::put(Releases::V2_0_0);
+
+        T::DbWeight::get().reads_writes(2, writes)
+    }
     fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty) {
         Kitties::<T>::insert(kitty_id, kitty);
-        KittiesCount::<T>::put(kitty_id);
-        <KittyOwners<T>>::insert(kitty_id, owner);
+        KittiesCount::<T>::put(kitty_id + 1u32.into());
+        Self::set_owner(kitty_id, owner);
+    }
+
+    /// The only place where the owner of a kitty changes. Keeps `KittyOwners`,
+    /// `OwnedKitties` and `KittyPrices` in sync: the kitty leaves the previous
+    /// owner's list, joins the new owner's list and any pending ask is dropped.
+    fn set_owner(kitty_id: T::KittyIndex, new_owner: &T::AccountId) {
+        if let Some(old_owner) = <KittyOwners<T>>::get(kitty_id) {
+            OwnedKitties::<T>::remove(&old_owner, kitty_id);
+        }
+        <KittyPrices<T>>::remove(kitty_id);
+        OwnedKitties::<T>::append(new_owner, kitty_id);
+        <KittyOwners<T>>::insert(kitty_id, new_owner);
     }
 
     fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
@@ -224,6 +285,8 @@ impl<T: Trait> Module<T> {
          let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
          let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
+         ensure!(Self::kitty_owner(kitty_id_1) == Some(sender.clone()), Error::<T>::RequireOwner);
+         ensure!(Self::kitty_owner(kitty_id_2) == Some(sender.clone()), Error::<T>::RequireOwner);
          ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
 
          let kitty_id = Self::next_kitty_id()?;
@@ -244,112 +307,16 @@ impl<T: Trait> Module<T> {
 
     fn do_transfer(sender: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
         // 判断小猫归属，如果属于sender，才可以进行转移
-        let item = <OwnedKitties<T>>::get((&sender, Some(kitty_id)));
-        match item {
-            Some(_v) => {
-                OwnedKitties::<T>::remove(sender, kitty_id);
-                OwnedKitties::<T>::append(to, kitty_id);
-            }
-            None => return Err(Error::<T>::NoPermissionToTransferKitty.into()),
-        };
+        ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NoPermissionToTransferKitty);
+
+        Self::set_owner(kitty_id, to);
 
         Ok(())
     }
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    use sp_core::H256;
-	use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
-	use sp_runtime::{
-		traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
-	};
-	use frame_system as system;
-
-	impl_outer_origin! {
-		pub enum Origin for Test {}
-	}
-
-	// For testing the module, we construct most of a mock runtime. This means
-	// first constructing a configuration type (`Test`) which `impl`s each of the
-	// configuration traits of modules we want to use.
-	#[derive(Clone, Eq, PartialEq, Debug)]
-	pub struct Test;
-	parameter_types! {
-		pub const BlockHashCount: u64 = 250;
-		pub const MaximumBlockWeight: Weight = 1024;
-		pub const MaximumBlockLength: u32 = 2 * 1024;
-		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-	}
-	impl system::Trait for Test {
-        type BaseCallFilter = ();
-		type Origin = Origin;
-		type Call = ();
-		type Index = u64;
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type BlockHashCount = BlockHashCount;
-		type MaximumBlockWeight = MaximumBlockWeight;
-		type DbWeight = ();
-		type BlockExecutionWeight = ();
-		type ExtrinsicBaseWeight = ();
-		type MaximumExtrinsicWeight = MaximumBlockWeight;
-		type MaximumBlockLength = MaximumBlockLength;
-		type AvailableBlockRatio = AvailableBlockRatio;
-		type Version = ();
-        type SystemWeightInfo = ();
-        type PalletInfo = ();
-		type AccountData = ();
-		type OnNewAccount = ();
-		type OnKilledAccount = ();
-    }
-
-    type Randomness = pallet_randomness_collective_flip::Module<Test>;
-
-	impl Trait for Test {
-        // type Event = ();
-        type Randomness = Randomness;
-        type KittyIndex = u32;
-    }
-
-    pub type Kitties = Module<Test>;
-    // pub type System = frame_system::Module<Test>;
-
-    // fn run_to_block(n: u64) {
-    //     while System::block_number() < n {
-    //         Kitties::on_finalize(System::block_number());
-    //         System::on_finalize(System::block_number());
-    //         System::set_block_number(System::block_number() + 1);
-    //         System::on_initialize(System::block_number());
-    //         Kitties::on_initialize(System::block_number());
-    //     }
-    // }
-
-	// This function basically just builds a genesis storage key/value store according to
-	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
-	}
+mod mock;
 
-	#[test]
-	fn owned_kitties_can_append_values() {
-		new_test_ext().execute_with(|| {
-            // run_to_block(10);
-			assert_eq!(Kitties::create(Origin::signed(1)), Ok(()));
-		});
-	}
-
-	// #[test]
-	// fn owned_kitties_can_remove_values() {
-	// 	new_test_ext().execute_with(|| {
-
-	// 	});
-	// }
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests;
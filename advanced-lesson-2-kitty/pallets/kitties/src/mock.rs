@@ -0,0 +1,110 @@
+use crate::{Module, Trait};
+use sp_core::H256;
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::{Weight, constants::RocksDbWeight}};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+};
+use frame_system as system;
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+mod kitties {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		kitties<T>,
+	}
+}
+
+// Configure a mock runtime to test the pallet.
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+
+impl system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = RocksDbWeight;
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Trait for Test {
+	type MaxLocks = MaxLocks;
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type Randomness = Randomness;
+	type KittyIndex = u32;
+	type Currency = Balances;
+}
+
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Randomness = pallet_randomness_collective_flip::Module<Test>;
+pub type Kitties = Module<Test>;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(1, 1_000),
+			(2, 1_000),
+			(3, 1_000),
+			(4, 1_000),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+
+	crate::GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on block 0.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
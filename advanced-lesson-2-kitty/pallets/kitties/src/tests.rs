@@ -0,0 +1,334 @@
+use crate::{Error, RawEvent, OwnedKitties, KittyPrices, KittyOwners, KittiesCount, Kitty, Releases, StorageVersion, mock::*};
+use frame_support::{
+	assert_ok, assert_noop, StorageMap, StorageValue, IterableStorageMap,
+	traits::{Get, OnRuntimeUpgrade}, weights::constants::RocksDbWeight,
+};
+
+const ACCOUNTS: [u64; 4] = [1, 2, 3, 4];
+
+fn last_event() -> TestEvent {
+	System::events().pop().expect("event expected").event
+}
+
+/// Walk the `OwnedKitties` linked list of `account` from head to tail.
+fn owned_list(account: u64) -> Vec<u32> {
+	let head = OwnedKitties::<Test>::get((account, None::<u32>));
+	let mut ids = Vec::new();
+	let mut cursor = head.as_ref().and_then(|item| item.next);
+	while let Some(kitty_id) = cursor {
+		ids.push(kitty_id);
+		cursor = OwnedKitties::<Test>::get((account, Some(kitty_id)))
+			.expect("every linked kitty has an item")
+			.next;
+	}
+	// The head points back to the tail.
+	assert_eq!(head.and_then(|item| item.prev), ids.last().cloned());
+	ids
+}
+
+/// `KittyOwners`, `OwnedKitties` and `KittyPrices` must always agree.
+fn assert_invariants() {
+	let count = Kitties::kitties_count();
+	let mut listed = 0;
+
+	for account in ACCOUNTS.iter() {
+		let mut owned = owned_list(*account);
+		listed += owned.len() as u32;
+		owned.sort();
+
+		let mut expected: Vec<u32> = (0..count)
+			.filter(|id| Kitties::kitty_owner(id) == Some(*account))
+			.collect();
+		expected.sort();
+
+		assert_eq!(owned, expected, "owned list of {} out of sync", account);
+	}
+
+	for id in 0..count {
+		assert!(Kitties::kitties(id).is_some());
+		assert!(Kitties::kitty_owner(id).is_some());
+	}
+	assert!(Kitties::kitties(count).is_none());
+	assert!(Kitties::kitty_owner(count).is_none());
+	assert_eq!(listed, count);
+
+	for (kitty_id, _) in KittyPrices::<Test>::iter() {
+		assert!(kitty_id < count, "price set for unknown kitty {}", kitty_id);
+	}
+}
+
+#[test]
+fn owned_kitties_can_append_values() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		assert_eq!(Kitties::kitties_count(), 2);
+		assert_eq!(owned_list(1), vec![0, 1]);
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Created(1, 1)));
+		assert_invariants();
+	});
+}
+
+#[test]
+fn owned_kitties_can_remove_values() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 1));
+
+		assert_eq!(owned_list(1), vec![0, 2]);
+		assert_eq!(owned_list(2), vec![1]);
+		assert_invariants();
+	});
+}
+
+#[test]
+fn transfer_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		assert_noop!(
+			Kitties::transfer(Origin::signed(2), 3, 0),
+			Error::<Test>::NoPermissionToTransferKitty
+		);
+
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+		assert_eq!(Kitties::kitty_owner(0), Some(2));
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Transferred(1, 2, 0)));
+
+		// The new owner can move it on.
+		assert_ok!(Kitties::transfer(Origin::signed(2), 3, 0));
+		assert_eq!(Kitties::kitty_owner(0), Some(3));
+		assert_invariants();
+	});
+}
+
+#[test]
+fn transfer_clears_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+		assert_eq!(Kitties::kitty_price(0), None);
+		assert_noop!(Kitties::buy(Origin::signed(3), 0, 10), Error::<Test>::NotForSale);
+	});
+}
+
+#[test]
+fn ask_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		assert_noop!(Kitties::ask(Origin::signed(2), 0, Some(10)), Error::<Test>::RequireOwner);
+
+		assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+		assert_eq!(Kitties::kitty_price(0), Some(10));
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Ask(1, 0, Some(10))));
+
+		assert_ok!(Kitties::ask(Origin::signed(1), 0, None));
+		assert_eq!(Kitties::kitty_price(0), None);
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Ask(1, 0, None)));
+	});
+}
+
+#[test]
+fn buy_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		assert_noop!(Kitties::buy(Origin::signed(2), 1, 10), Error::<Test>::InvalidKittyId);
+		assert_noop!(Kitties::buy(Origin::signed(2), 0, 10), Error::<Test>::NotForSale);
+
+		assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+		assert_noop!(Kitties::buy(Origin::signed(2), 0, 9), Error::<Test>::PriceTooLow);
+
+		assert_ok!(Kitties::buy(Origin::signed(2), 0, 10));
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Sold(1, 2, 0, 10)));
+		assert_eq!(Balances::free_balance(1), 1_010);
+		assert_eq!(Balances::free_balance(2), 990);
+		assert_eq!(Kitties::kitty_price(0), None);
+		assert_invariants();
+	});
+}
+
+#[test]
+fn buyer_owns_the_kitty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+		assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+		assert_ok!(Kitties::buy(Origin::signed(2), 0, 10));
+
+		assert_eq!(owned_list(1), Vec::<u32>::new());
+		assert_eq!(owned_list(2), vec![1, 0]);
+
+		// The previous owner lost every right on the kitty.
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 3, 0),
+			Error::<Test>::NoPermissionToTransferKitty
+		);
+		assert_noop!(Kitties::ask(Origin::signed(1), 0, Some(5)), Error::<Test>::RequireOwner);
+
+		// The buyer can ask, breed and transfer it.
+		assert_ok!(Kitties::ask(Origin::signed(2), 0, Some(20)));
+		assert_ok!(Kitties::breed(Origin::signed(2), 0, 1));
+		assert_ok!(Kitties::transfer(Origin::signed(2), 3, 0));
+		assert_invariants();
+	});
+}
+
+#[test]
+fn breed_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+
+		assert_noop!(Kitties::breed(Origin::signed(1), 0, 3), Error::<Test>::InvalidKittyId);
+		assert_noop!(Kitties::breed(Origin::signed(1), 0, 0), Error::<Test>::RequireDifferentParent);
+		assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::RequireOwner);
+
+		assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+		assert_eq!(Kitties::kitty_owner(3), Some(1));
+		assert_eq!(last_event(), TestEvent::kitties(RawEvent::Created(1, 3)));
+		assert_invariants();
+	});
+}
+
+/// A tiny xorshift generator, so random call sequences are reproducible.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, n: u64) -> u64 {
+		self.next() % n
+	}
+
+	fn account(&mut self) -> u64 {
+		ACCOUNTS[self.below(ACCOUNTS.len() as u64) as usize]
+	}
+}
+
+#[test]
+fn invariants_hold_after_random_calls() {
+	for seed in 1..=20u64 {
+		new_test_ext().execute_with(|| {
+			let mut rng = Rng(seed * 0x9E37_79B9_7F4A_7C15);
+
+			for _ in 0..200 {
+				let who = rng.account();
+				// Pick one past the last id too, to exercise the error paths.
+				let kitty_id = rng.below(Kitties::kitties_count() as u64 + 1) as u32;
+
+				// Failed calls must leave storage untouched, so results are ignored here.
+				let _ = match rng.below(5) {
+					0 => Kitties::create(Origin::signed(who)),
+					1 => Kitties::transfer(Origin::signed(who), rng.account(), kitty_id),
+					2 => {
+						let other = rng.below(Kitties::kitties_count() as u64 + 1) as u32;
+						Kitties::breed(Origin::signed(who), kitty_id, other)
+					},
+					3 => {
+						let price = match rng.below(3) {
+							0 => None,
+							_ => Some(rng.below(50)),
+						};
+						Kitties::ask(Origin::signed(who), kitty_id, price)
+					},
+					_ => Kitties::buy(Origin::signed(who), kitty_id, rng.below(50)),
+				};
+
+				assert_invariants();
+			}
+		});
+	}
+}
+
+/// Lay out `count` kitties of account 1 the way `V1_0_0` chains did, with
+/// `KittiesCount` holding the id of the last one.
+fn put_v1_kitties(count: u32) {
+	StorageVersion::put(Releases::V1_0_0);
+	for kitty_id in 0..count {
+		crate::Kitties::<Test>::insert(kitty_id, Kitty([kitty_id as u8; 16]));
+		KittyOwners::<Test>::insert(kitty_id, 1);
+		OwnedKitties::<Test>::append(&1, kitty_id);
+	}
+	KittiesCount::<Test>::put(count.saturating_sub(1));
+}
+
+#[test]
+fn migrate_to_v2_moves_kitties_count_past_the_last_kitty() {
+	new_test_ext().execute_with(|| {
+		put_v1_kitties(3);
+		assert_eq!(Kitties::storage_version(), Releases::V1_0_0);
+		assert_eq!(Kitties::kitties_count(), 2);
+
+		let weight = Kitties::on_runtime_upgrade();
+
+		assert_eq!(weight, RocksDbWeight::get().reads_writes(3, 2));
+		assert_eq!(Kitties::storage_version(), Releases::V2_0_0);
+		assert_eq!(Kitties::kitties_count(), 3);
+		assert_invariants();
+
+		// The last kitty is no longer overwritten by the next one.
+		assert_ok!(Kitties::create(Origin::signed(2)));
+		assert_eq!(Kitties::kitty_owner(2), Some(1));
+		assert_eq!(Kitties::kitty_owner(3), Some(2));
+		assert_invariants();
+	});
+}
+
+#[test]
+fn migrate_to_v2_keeps_empty_kitties_count() {
+	new_test_ext().execute_with(|| {
+		put_v1_kitties(0);
+
+		let weight = Kitties::on_runtime_upgrade();
+
+		assert_eq!(weight, RocksDbWeight::get().reads_writes(3, 1));
+		assert_eq!(Kitties::storage_version(), Releases::V2_0_0);
+		assert_eq!(Kitties::kitties_count(), 0);
+	});
+}
+
+#[test]
+fn migrate_to_v2_runs_once() {
+	new_test_ext().execute_with(|| {
+		put_v1_kitties(2);
+		Kitties::on_runtime_upgrade();
+		assert_eq!(Kitties::kitties_count(), 2);
+
+		// Only the version is read on later upgrades.
+		let weight = Kitties::on_runtime_upgrade();
+
+		assert_eq!(weight, RocksDbWeight::get().reads(1));
+		assert_eq!(Kitties::kitties_count(), 2);
+		assert_invariants();
+	});
+}
+
+#[test]
+fn new_chains_start_on_the_latest_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Kitties::storage_version(), Releases::V2_0_0);
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(1)));
+
+		// Nothing to migrate, so the upgrade must not move `KittiesCount`.
+		let weight = Kitties::on_runtime_upgrade();
+
+		assert_eq!(weight, RocksDbWeight::get().reads(1));
+		assert_eq!(Kitties::kitties_count(), 2);
+		assert_invariants();
+	});
+}
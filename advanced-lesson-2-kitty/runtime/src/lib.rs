@@ -268,9 +268,10 @@ impl pallet_template::Trait for Runtime {
 }
 
 impl pallet_kitties::Trait for Runtime {
-	// type Event = Event;
+	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
 	type KittyIndex = u32;
+	type Currency = Balances;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -290,7 +291,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
-		KittiesModule: pallet_kitties::{Module, Call, Storage},
+		KittiesModule: pallet_kitties::{Module, Call, Storage, Config, Event<T>},
 	}
 );
 
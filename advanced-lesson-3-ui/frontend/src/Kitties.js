@@ -6,85 +6,85 @@ import { TxButton } from './substrate-lib/components';
 
 import KittyCards from './KittyCards';
 
+// Kitties fetched per `kitties_kittiesOf` call.
+const PAGE_SIZE = 50;
+
+const toCard = info => ({
+  id: info.id.toNumber(),
+  dna: Array.from(info.dna.toU8a()),
+  owner: info.owner.toString(),
+  price: info.price.isSome ? info.price.unwrap().toHuman() : null
+});
+
 export default function Kitties (props) {
   const { api, keyring } = useSubstrate();
   const { accountPair } = props;
 
-  const [kittyCnt, setKittyCnt] = useState(0);
-  const [kittyDNAs, setKittyDNAs] = useState([]);
-  const [kittyOwners, setKittyOwners] = useState([]);
-  const [kittyPrices, setKittyPrices] = useState([]);
-  const [kitties, setKitties] = useState([]);
+  const [blockHash, setBlockHash] = useState(null);
+  const [ownedKitties, setOwnedKitties] = useState([]);
+  const [kittiesForSale, setKittiesForSale] = useState([]);
   const [status, setStatus] = useState('');
 
-  const fetchKittyCnt = async () => {
-    /* TODO: 加代码，从 substrate 端读取数据过来 */
+  const subscribeNewHeads = () => {
     if (!api || !keyring) {
       return;
     }
-    let unsubscribe = null
-    // console.log("----:", api.query.kittiesModule)
-    unsubscribe = await api.query.kittiesModule.kittiesCount(count => {
-      setKittyCnt(count.toNumber());
-    })
+    let unsubscribe = null;
+
+    // Both lists are refreshed on every new block.
+    api.rpc.chain.subscribeNewHeads(header => setBlockHash(header.hash.toHex()))
+      .then(unsub => { unsubscribe = unsub; })
+      .catch(console.error);
 
     return () => unsubscribe && unsubscribe();
   };
 
-  const fetchKitties = () => {
-    /* TODO: 加代码，从 substrate 端读取数据过来 */
-    let unsubDna = null;
-    let unsubOwner = null;
-    let unsubPrice = null;
+  const fetchOwnedKitties = () => {
+    if (!api || !blockHash || !accountPair) {
+      return;
+    }
+    let cancelled = false;
 
     const asyncFetch = async () => {
-      const kittyIndices = [...Array(kittyCnt).keys()];
-
-      unsubDna = await api.query.kittiesModule.kitties.multi(
-        kittyIndices,
-        dnas => setKittyDNAs(dnas.map(dna => dna.value.toU8a()))
-      );
-
-      unsubOwner = await api.query.kittiesModule.kittyOwners.multi(
-        kittyIndices,
-        owners => setKittyOwners(owners.map(owner => owner.toHuman()))
-      );
-
-      unsubPrice = await api.query.kittiesModule.kittyPrices.multi(
-        kittyIndices,
-        prices => setKittyPrices(prices.map(price => price.isSome && price.toHuman()))
-      );
+      const kitties = [];
+      let cursor = null;
+      do {
+        const page = await api.rpc.kitties.kittiesOf(accountPair.address, cursor, PAGE_SIZE, blockHash);
+        kitties.push(...page.kitties.map(toCard));
+        cursor = page.next.isSome ? page.next.unwrap() : null;
+      } while (cursor !== null);
+
+      !cancelled && setOwnedKitties(kitties);
     };
 
-    asyncFetch();
+    asyncFetch().catch(console.error);
 
-    // return the unsubscription cleanup function
-    return () => {
-      unsubDna && unsubDna();
-      unsubOwner && unsubOwner();
-      unsubPrice && unsubPrice();
-    };
+    return () => { cancelled = true; };
   };
 
-  const populateKitties = () => {
-    /* TODO: 加代码，从 substrate 端读取数据过来 */
-    const kittyIndices = [...Array(kittyCnt).keys()];
-    const kitties = kittyIndices.map(idx => ({
-      id: idx,
-      dna: kittyDNAs[ind],
-      owner: kittyOwners[ind],
-      price: kittyPrices[ind]
-    }));
-    setKitties(kitties);
+  const fetchKittiesForSale = () => {
+    if (!api || !blockHash) {
+      return;
+    }
+    let cancelled = false;
+
+    api.rpc.kitties.kittiesForSale(blockHash)
+      .then(kitties => !cancelled && setKittiesForSale(kitties.map(toCard)))
+      .catch(console.error);
+
+    return () => { cancelled = true; };
   };
 
-  useEffect(fetchKittyCnt, [api, keyring]);
-  useEffect(fetchKitties, [api, kittyCnt]);
-  useEffect(populateKitties, [kittyDNAs, kittyOwners]);
+  useEffect(subscribeNewHeads, [api, keyring]);
+  useEffect(fetchOwnedKitties, [api, blockHash, accountPair]);
+  useEffect(fetchKittiesForSale, [api, blockHash]);
 
   return <Grid.Column width={16}>
     <h1>小毛孩</h1>
-    <KittyCards kitties={kitties} accountPair={accountPair} setStatus={setStatus}/>
+    <h2>我的小毛孩</h2>
+    <KittyCards kitties={ownedKitties} accountPair={accountPair} setStatus={setStatus}/>
+    <h2>出售中</h2>
+    <KittyCards kitties={kittiesForSale} accountPair={accountPair} setStatus={setStatus}/>
     <Form style={{ margin: '1em 0' }}>
       <Form.Field style={{ textAlign: 'center' }}>
         <TxButton
@@ -83,26 +83,31 @@ const IMAGES = {
   ]
 };
 
-const dnaToAttributes = dna => {
-  const attribute = (index, type) => IMAGES[type][dna[index] % IMAGES[type].length];
+// Mirrors `KittyTraits::from_dna` on chain: the body image is the `pattern` trait.
+const dnaToTraits = dna => ({
+  pattern: dna[0] % IMAGES.body.length,
+  eyes: dna[1] % IMAGES.eyes.length,
+  accessory: dna[2] % IMAGES.accessory.length,
+  fur: dna[3] % IMAGES.fur.length,
+  mouth: dna[4] % IMAGES.mouth.length
+});
 
-  return {
-    body: attribute(0, 'body'),
-    eyes: attribute(1, 'eyes'),
-    accessory: attribute(2, 'accessory'),
-    fur: attribute(3, 'fur'),
-    mouth: attribute(4, 'mouth')
-  };
-};
+const traitsToAttributes = traits => ({
+  body: IMAGES.body[traits.pattern],
+  eyes: IMAGES.eyes[traits.eyes],
+  accessory: IMAGES.accessory[traits.accessory],
+  fur: IMAGES.fur[traits.fur],
+  mouth: IMAGES.mouth[traits.mouth]
+});
 
 const KittyAvatar = props => {
   const outerStyle = { height: '160px', position: 'relative', width: '50%' };
   const innerStyle = { height: '150px', position: 'absolute', top: '3%', left: '50%' };
-  const { dna } = props;
+  const { dna, traits } = props;
 
-  if (!dna) return null;
+  if (!dna && !traits) return null;
 
-  const cat = dnaToAttributes(dna);
+  const cat = traitsToAttributes(traits || dnaToTraits(dna));
   return <div style={outerStyle}>
     <img alt='body' src={cat.body} style={innerStyle} />
     <img alt='fur' src={cat.fur} style={innerStyle} />
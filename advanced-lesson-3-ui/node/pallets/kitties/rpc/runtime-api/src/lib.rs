@@ -0,0 +1,91 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// Number of variants of each trait, one per avatar image.
+pub const PATTERN_VARIANTS: u8 = 15;
+pub const EYES_VARIANTS: u8 = 15;
+pub const ACCESSORY_VARIANTS: u8 = 20;
+pub const FUR_VARIANTS: u8 = 10;
+pub const MOUTH_VARIANTS: u8 = 10;
+
+/// The visible traits of a kitty. Each one is the index of an avatar image,
+/// the front end draws `KittyAvatar` from the same bytes of the DNA.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittyTraits {
+	pub pattern: u8,
+	pub eyes: u8,
+	pub accessory: u8,
+	pub fur: u8,
+	pub mouth: u8,
+}
+
+impl KittyTraits {
+	/// Decode the traits from the DNA. Only the first five bytes are expressed.
+	pub fn from_dna(dna: &[u8; 16]) -> Self {
+		KittyTraits {
+			pattern: dna[0] % PATTERN_VARIANTS,
+			eyes: dna[1] % EYES_VARIANTS,
+			accessory: dna[2] % ACCESSORY_VARIANTS,
+			fur: dna[3] % FUR_VARIANTS,
+			mouth: dna[4] % MOUTH_VARIANTS,
+		}
+	}
+}
+
+/// Everything a client needs to display a kitty.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittyInfo<AccountId, KittyIndex, Balance, BlockNumber> {
+	pub id: KittyIndex,
+	pub owner: AccountId,
+	pub dna: [u8; 16],
+	pub traits: KittyTraits,
+	/// None means not for sale.
+	pub price: Option<Balance>,
+	/// The kitties this one was bred from. None for created kitties.
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// 0 for created kitties, one more than the highest parent generation for bred ones.
+	pub generation: u16,
+	pub birth: BlockNumber,
+	/// The first block at which the kitty can breed again.
+	pub ready_at: BlockNumber,
+}
+
+/// A page of kitties, with the cursor to pass to get the next page.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittiesPage<AccountId, KittyIndex, Balance, BlockNumber> {
+	pub kitties: Vec<KittyInfo<AccountId, KittyIndex, Balance, BlockNumber>>,
+	/// None once there are no more kitties.
+	pub next: Option<KittyIndex>,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Balance, BlockNumber> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Kitties of `owner`, starting at `cursor` or at the first one when `None`.
+		fn kitties_of(
+			owner: AccountId,
+			cursor: Option<KittyIndex>,
+			limit: u32,
+		) -> KittiesPage<AccountId, KittyIndex, Balance, BlockNumber>;
+
+		/// All kitties for sale, cheapest first.
+		fn kitties_for_sale() -> Vec<KittyInfo<AccountId, KittyIndex, Balance, BlockNumber>>;
+	}
+}
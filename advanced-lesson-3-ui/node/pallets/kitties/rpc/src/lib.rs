@@ -0,0 +1,109 @@
+//! RPC interface for the kitties pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittiesPage, KittyInfo};
+
+/// Kitties RPC methods.
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Balance, BlockNumber> {
+	/// Kitties of `owner`, `limit` at a time. Pass the returned `next` as `cursor`
+	/// to get the following page.
+	#[rpc(name = "kitties_kittiesOf")]
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		cursor: Option<KittyIndex>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<KittiesPage<AccountId, KittyIndex, Balance, BlockNumber>>;
+
+	/// All kitties for sale, cheapest first.
+	#[rpc(name = "kitties_kittiesForSale")]
+	fn kitties_for_sale(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<KittyInfo<AccountId, KittyIndex, Balance, BlockNumber>>>;
+}
+
+/// A struct that implements the [`KittiesApi`].
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Kitties { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query kitties.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Balance, BlockNumber>
+	KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex, Balance, BlockNumber>
+	for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance, BlockNumber>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		cursor: Option<KittyIndex>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<KittiesPage<AccountId, KittyIndex, Balance, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.kitties_of(&at, owner, cursor, limit).map_err(runtime_error)
+	}
+
+	fn kitties_for_sale(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyInfo<AccountId, KittyIndex, Balance, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.kitties_for_sale(&at).map_err(runtime_error)
+	}
+}
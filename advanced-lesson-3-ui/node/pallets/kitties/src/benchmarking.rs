@@ -0,0 +1,310 @@
+//! Kitties pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+/// An account with enough balance for any number of kitty deposits and purchases.
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
+
+/// Fill the `OwnedKitties` list of `owner` with `n` kitties.
+fn create_kitties<T: Trait>(owner: &T::AccountId, n: u32) -> Result<(), &'static str> {
+	for _ in 0..n {
+		Module::<T>::create(RawOrigin::Signed(owner.clone()).into())?;
+	}
+	Ok(())
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = Module::<T>::kitties_count();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	breed {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id_1 = Module::<T>::kitties_count();
+		let kitty_id_2 = kitty_id_1 + 1u32.into();
+		create_kitties::<T>(&caller, 2)?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id_1, kitty_id_2)
+	verify {
+		let child_id = kitty_id_2 + 1u32.into();
+		assert_eq!(Module::<T>::kitty_owner(child_id), Some(caller));
+	}
+
+	offer_sire {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, 1)?;
+		let fee = T::KittyDeposit::get();
+	}: _(RawOrigin::Signed(caller), 0u32.into(), fee)
+	verify {
+		assert_eq!(Module::<T>::sire_offer(T::KittyIndex::from(0u32)), Some(fee));
+	}
+
+	cancel_sire_offer {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, 1)?;
+		Module::<T>::offer_sire(RawOrigin::Signed(caller.clone()).into(), 0u32.into(), T::KittyDeposit::get())?;
+	}: _(RawOrigin::Signed(caller), 0u32.into())
+	verify {
+		assert_eq!(Module::<T>::sire_offer(T::KittyIndex::from(0u32)), None);
+	}
+
+	breed_with_sire {
+		let sire_owner = funded_account::<T>("sire_owner", 0);
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&sire_owner, 1)?;
+		create_kitties::<T>(&caller, 1)?;
+		let sire_id: T::KittyIndex = 0u32.into();
+		let matron_id: T::KittyIndex = 1u32.into();
+		let fee = T::KittyDeposit::get();
+		Module::<T>::offer_sire(RawOrigin::Signed(sire_owner).into(), sire_id, fee)?;
+	}: _(RawOrigin::Signed(caller.clone()), matron_id, sire_id, fee)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(T::KittyIndex::from(2u32)), Some(caller));
+	}
+
+	transfer {
+		let caller = funded_account::<T>("caller", 0);
+		let recipient = funded_account::<T>("recipient", 0);
+		create_kitties::<T>(&caller, 1)?;
+		let kitty_id = Module::<T>::kitties_of(caller.clone(), None, 1).kitties[0].id;
+	}: _(RawOrigin::Signed(caller), recipient.clone(), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(recipient));
+	}
+
+	ask {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, 1)?;
+		let price = Some(T::KittyDeposit::get());
+	}: _(RawOrigin::Signed(caller), 0u32.into(), price)
+	verify {
+		assert_eq!(Module::<T>::kitty_price(T::KittyIndex::from(0u32)), price);
+	}
+
+	// Worst case: the released kitty is listed, offered as a sire and approved.
+	release {
+		let caller = funded_account::<T>("caller", 0);
+		let spender: T::AccountId = account("spender", 0, SEED);
+		create_kitties::<T>(&caller, 1)?;
+		let kitty_id: T::KittyIndex = 0u32.into();
+		let price = T::KittyDeposit::get();
+		Module::<T>::ask(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(price))?;
+		Module::<T>::offer_sire(RawOrigin::Signed(caller.clone()).into(), kitty_id, price)?;
+		Module::<T>::approve(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(spender))?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), None);
+	}
+
+	buy {
+		let seller = funded_account::<T>("seller", 0);
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&seller, 1)?;
+		let kitty_id = Module::<T>::kitties_of(seller.clone(), None, 1).kitties[0].id;
+		let price = T::KittyDeposit::get();
+		Module::<T>::ask(RawOrigin::Signed(seller).into(), kitty_id, Some(price))?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	create_auction {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, 1)?;
+		let price = T::KittyDeposit::get();
+		let kind = AuctionKind::Dutch { start_price: price, end_price: price };
+	}: _(RawOrigin::Signed(caller), 0u32.into(), kind, 10u32.into())
+	verify {
+		assert!(Module::<T>::auction(T::KittyIndex::from(0u32)).is_some());
+	}
+
+	// Worst case: a Dutch bid, which pays the seller and takes the kitty right away.
+	bid {
+		let seller = funded_account::<T>("seller", 0);
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&seller, 1)?;
+		let kitty_id: T::KittyIndex = 0u32.into();
+		let price = T::KittyDeposit::get();
+		let kind = AuctionKind::Dutch { start_price: price, end_price: price };
+		Module::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, kind, 10u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	cancel_auction {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, 1)?;
+		let kitty_id: T::KittyIndex = 0u32.into();
+		let kind = AuctionKind::English { min_bid: T::KittyDeposit::get() };
+		Module::<T>::create_auction(RawOrigin::Signed(caller.clone()).into(), kitty_id, kind, 10u32.into())?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::auction(kitty_id), None);
+	}
+
+	// Worst case: the caller is an operator of the owner, checked after ownership.
+	approve {
+		let owner = funded_account::<T>("owner", 0);
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let spender: T::AccountId = account("spender", 0, SEED);
+		create_kitties::<T>(&owner, 1)?;
+		Module::<T>::set_approval_for_all(RawOrigin::Signed(owner).into(), caller.clone(), true)?;
+	}: _(RawOrigin::Signed(caller), 0u32.into(), Some(spender.clone()))
+	verify {
+		assert_eq!(Module::<T>::kitty_approval(T::KittyIndex::from(0u32)), Some(spender));
+	}
+
+	set_approval_for_all {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert!(Module::<T>::is_approved_for_all(&caller, &operator));
+	}
+
+	// Worst case: the caller is an operator, checked last, and an approval to clear is set.
+	transfer_from {
+		let owner = funded_account::<T>("owner", 0);
+		let recipient = funded_account::<T>("recipient", 0);
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let spender: T::AccountId = account("spender", 0, SEED);
+		create_kitties::<T>(&owner, 1)?;
+		let kitty_id = Module::<T>::kitties_of(owner.clone(), None, 1).kitties[0].id;
+		Module::<T>::approve(RawOrigin::Signed(owner.clone()).into(), kitty_id, Some(spender))?;
+		Module::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+	}: _(RawOrigin::Signed(caller), owner, recipient.clone(), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(recipient));
+		assert_eq!(Module::<T>::kitty_approval(kitty_id), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn create() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+		});
+	}
+
+	#[test]
+	fn breed() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_breed::<Test>());
+		});
+	}
+
+	#[test]
+	fn offer_sire() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_offer_sire::<Test>());
+		});
+	}
+
+	#[test]
+	fn cancel_sire_offer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_cancel_sire_offer::<Test>());
+		});
+	}
+
+	#[test]
+	fn breed_with_sire() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_breed_with_sire::<Test>());
+		});
+	}
+
+	#[test]
+	fn transfer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_transfer::<Test>());
+		});
+	}
+
+	#[test]
+	fn ask() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_ask::<Test>());
+		});
+	}
+
+	#[test]
+	fn release() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_release::<Test>());
+		});
+	}
+
+	#[test]
+	fn buy() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_buy::<Test>());
+		});
+	}
+
+	#[test]
+	fn create_auction() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create_auction::<Test>());
+		});
+	}
+
+	#[test]
+	fn bid() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_bid::<Test>());
+		});
+	}
+
+	#[test]
+	fn cancel_auction() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_cancel_auction::<Test>());
+		});
+	}
+
+	#[test]
+	fn approve() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_approve::<Test>());
+		});
+	}
+
+	#[test]
+	fn set_approval_for_all() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_approval_for_all::<Test>());
+		});
+	}
+
+	#[test]
+	fn transfer_from() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_from::<Test>());
+		});
+	}
+}
@@ -2,35 +2,114 @@
 
 use codec::{Encode, Decode};
 use frame_support::{
-	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, Parameter,
-	debug,
-	traits::{Randomness, Currency, ExistenceRequirement},
+	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, StorageDoubleMap, Parameter,
+	IterableStorageMap, debug,
+	traits::{Randomness, Currency, ReservableCurrency, ExistenceRequirement, BalanceStatus, Get},
+	weights::Weight,
+	storage::migration::take_storage_item,
+	transactional,
+	Blake2_128Concat,
 };
 use sp_io::hashing::blake2_128;
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::{DispatchError, RuntimeDebug, traits::{AtLeast32Bit, Member, Bounded,}};
+use sp_runtime::{DispatchError, RuntimeDebug, traits::{AtLeast32Bit, Member, Bounded, One, Saturating, Zero, SaturatedConversion}, DispatchResult};
+use sp_std::prelude::*;
 use crate::link::{LinkedList, LinkedItem};
+pub use pallet_kitties_rpc_runtime_api::{KittyInfo, KittiesPage, KittyTraits};
+pub use weights::WeightInfo;
 
 mod link;
+mod benchmarking;
+pub mod weights;
+
+/// Most kitties returned by a single `kitties_of` call.
+pub const MAX_KITTIES_PAGE: u32 = 100;
 
 #[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq)]
-pub struct Kitty(pub [u8; 16]);
+pub struct Kitty<KittyIndex, BlockNumber> {
+	pub dna: [u8; 16],
+	/// The kitties this one was bred from. None for created kitties.
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// 0 for created kitties, one more than the highest parent generation for bred ones.
+	pub generation: u16,
+	pub birth: BlockNumber,
+	/// The first block at which the kitty can breed again.
+	pub ready_at: BlockNumber,
+}
+
+impl<KittyIndex, BlockNumber> Kitty<KittyIndex, BlockNumber> {
+	/// The visible traits encoded in the DNA.
+	pub fn traits(&self) -> KittyTraits {
+		KittyTraits::from_dna(&self.dna)
+	}
+}
+
+/// How the price of an auction is set.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub enum AuctionKind<Balance> {
+	/// Ascending bids, kept reserved. The highest one wins when the auction ends.
+	English { min_bid: Balance },
+	/// The price falls linearly from `start_price` to `end_price`. The first bid
+	/// at the current price wins right away.
+	Dutch { start_price: Balance, end_price: Balance },
+}
+
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+	pub seller: AccountId,
+	pub kind: AuctionKind<Balance>,
+	pub start: BlockNumber,
+	/// Last block accepting bids. The auction is settled at the start of the next one.
+	pub end: BlockNumber,
+	/// Highest bid of an English auction. (bidder, amount)
+	pub highest_bid: Option<(AccountId, Balance)>,
+}
 
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	type KittyIndex: Parameter + Member + AtLeast32Bit + Default + Copy;
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 	type Randomness: Randomness<Self::Hash>;
+
+	/// Blocks a generation 0 kitty has to wait between two breedings.
+	/// Each generation waits one more time this amount.
+	type BaseBreedingCooldown: Get<Self::BlockNumber>;
+
+	/// Reserved from the owner of each new kitty, returned when it is released.
+	type KittyDeposit: Get<BalanceOf<Self>>;
+
+	/// Most kitties an account can create or breed into.
+	type MaxKittiesPerAccount: Get<u32>;
+
+	/// Most auctions that can end in the same block, which bounds the settlement
+	/// done in `on_initialize`.
+	type MaxAuctionsPerBlock: Get<u32>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type KittyOf<T> = Kitty<<T as Trait>::KittyIndex, <T as system::Trait>::BlockNumber>;
+type AuctionOf<T> = Auction<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type KittyInfoOf<T> = KittyInfo<
+	<T as system::Trait>::AccountId,
+	<T as Trait>::KittyIndex,
+	BalanceOf<T>,
+	<T as system::Trait>::BlockNumber,
+>;
 type KittyLinkedItem<T> = LinkedItem<<T as Trait>::KittyIndex>;
-type OwnedKittiesList<T> = LinkedList<OwnedKitties<T>, <T as system::Trait>::AccountId, <T as Trait>::KittyIndex>;
+type OwnedKittiesList<T> = LinkedList<
+	OwnedKitties<T>,
+	OwnedKittiesCount<T>,
+	<T as system::Trait>::AccountId,
+	<T as Trait>::KittyIndex,
+>;
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Kitties {
 		/// Stores all the kitties, key is the kitty id / index
-		pub Kitties get(fn kitties): map hasher(blake2_128_concat) T::KittyIndex => Option<Kitty>;
+		pub Kitties get(fn kitties): map hasher(blake2_128_concat) T::KittyIndex => Option<KittyOf<T>>;
 
 		/// Stores the total number of kitties. i.e. the next kitty index
 		pub KittiesCount get(fn kitties_count): T::KittyIndex;
@@ -39,6 +118,18 @@ decl_storage! {
 		pub OwnedKitties get(fn owned_kitties): map hasher(blake2_128_concat)
 			(T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
 
+		/// Number of kitties in each owned kitties list.
+		pub OwnedKittiesCount get(fn owned_kitties_count): map hasher(blake2_128_concat)
+			T::AccountId => u32;
+
+		/// Whether the owned kitties lists have been rebuilt from `KittyOwners`.
+		/// New chains start with the lists already in place.
+		OwnedKittiesRebuilt get(fn owned_kitties_rebuilt) build(|_| true): bool;
+
+		/// Whether the kitties have been migrated from bare DNA to `Kitty` records.
+		/// New chains start with `Kitty` records already in place.
+		KittyRecordsMigrated get(fn kitty_records_migrated) build(|_| true): bool;
+
 		/// Store owner of each kitity.
 		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex =>
 			Option<T::AccountId>;
@@ -46,6 +137,30 @@ decl_storage! {
 		/// Get kitty price. None means not for sale.
 		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex =>
 			Option<BalanceOf<T>>;
+
+		/// Deposit reserved for each kitty. It moves along with the kitty.
+		pub KittyDeposits get(fn kitty_deposit): map hasher(blake2_128_concat) T::KittyIndex =>
+			BalanceOf<T>;
+
+		/// Account allowed to transfer a kitty on behalf of its owner.
+		pub KittyApprovals get(fn kitty_approval): map hasher(blake2_128_concat) T::KittyIndex =>
+			Option<T::AccountId>;
+
+		/// Operators allowed to transfer every kitty of an owner. (owner, operator)
+		pub OperatorApprovals get(fn is_approved_for_all): double_map hasher(blake2_128_concat) T::AccountId,
+			hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// Fee to breed with a kitty offered as a sire. None means not offered.
+		pub SireOffers get(fn sire_offer): map hasher(blake2_128_concat) T::KittyIndex =>
+			Option<BalanceOf<T>>;
+
+		/// Running auctions. A kitty in auction can't be transferred, bred or asked.
+		pub Auctions get(fn auction): map hasher(blake2_128_concat) T::KittyIndex =>
+			Option<AuctionOf<T>>;
+
+		/// Kitties whose auction ends at a block.
+		pub AuctionsEndingAt get(fn auctions_ending_at): map hasher(twox_64_concat) T::BlockNumber =>
+			Vec<T::KittyIndex>;
 	}
 }
 
@@ -57,6 +172,18 @@ decl_error! {
 		RequireOwner,
 		NotForSale,
 		PriceTooLow,
+		KittyOnCooldown,
+		NotOfferedAsSire,
+		KittyInAuction,
+		NotInAuction,
+		InvalidAuction,
+		AuctionEnded,
+		AuctionHasBids,
+		SellerCannotBid,
+		BidTooLow,
+		TooManyKitties,
+		NotApproved,
+		TooManyAuctions,
 	}
 }
 
@@ -64,16 +191,39 @@ decl_event!(
 	pub enum Event<T> where
 		<T as frame_system::Trait>::AccountId,
 		<T as Trait>::KittyIndex,
+		<T as frame_system::Trait>::BlockNumber,
 		Balance = BalanceOf<T>,
 	{
 		/// A kitty is created. (owner, kitty_id)
 		Created(AccountId, KittyIndex),
 		/// A kitty is transferred. (from, to, kitty_id)
 		Transferred(AccountId, AccountId, KittyIndex),
+		/// A spender is approved for a kitty, None when the approval is cleared. (owner, spender, kitty_id)
+		Approval(AccountId, Option<AccountId>, KittyIndex),
+		/// An operator is allowed or disallowed to transfer every kitty of an owner. (owner, operator, approved)
+		ApprovalForAll(AccountId, AccountId, bool),
+		/// A kitty is released and its deposit returned. (owner, kitty_id, deposit)
+		Released(AccountId, KittyIndex, Balance),
 		/// A kitty is available for sale. (owner, kitty_id, price)
 		Ask(AccountId, KittyIndex, Option<Balance>),
 		/// A kitty is sold. (from, to, kitty_id, price)
 		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// A kitty is offered as a sire. (owner, kitty_id, fee)
+		SireOffered(AccountId, KittyIndex, Balance),
+		/// A sire offer is withdrawn. (owner, kitty_id)
+		SireOfferCancelled(AccountId, KittyIndex),
+		/// A sire is rented. (sire_owner, payer, sire_id, matron_id, new_kitty_id, fee)
+		SireRented(AccountId, AccountId, KittyIndex, KittyIndex, KittyIndex, Balance),
+		/// A kitty is put up for auction. (seller, kitty_id, end)
+		AuctionCreated(AccountId, KittyIndex, BlockNumber),
+		/// A bid is placed in an English auction. (bidder, kitty_id, amount)
+		BidPlaced(AccountId, KittyIndex, Balance),
+		/// An auction is won. (seller, winner, kitty_id, price)
+		AuctionWon(AccountId, AccountId, KittyIndex, Balance),
+		/// An auction ended without a sale. (seller, kitty_id)
+		AuctionEnded(AccountId, KittyIndex),
+		/// An auction is cancelled by its seller. (seller, kitty_id)
+		AuctionCancelled(AccountId, KittyIndex),
 	}
 );
 
@@ -83,8 +233,32 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		/// Blocks a generation 0 kitty has to wait between two breedings.
+		const BaseBreedingCooldown: T::BlockNumber = T::BaseBreedingCooldown::get();
+
+		/// Reserved from the owner of each new kitty.
+		const KittyDeposit: BalanceOf<T> = T::KittyDeposit::get();
+
+		/// Most kitties an account can create or breed into.
+		const MaxKittiesPerAccount: u32 = T::MaxKittiesPerAccount::get();
+
+		/// Most auctions that can end in the same block.
+		const MaxAuctionsPerBlock: u32 = T::MaxAuctionsPerBlock::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_kitty_records().saturating_add(Self::rebuild_owned_kitties())
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let ended = now.saturating_sub(One::one());
+			<AuctionsEndingAt<T>>::take(ended).into_iter().fold(
+				T::DbWeight::get().reads_writes(1, 1),
+				|weight, kitty_id| weight.saturating_add(Self::settle_auction(kitty_id, ended)),
+			)
+		}
+
 		/// Create a new kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::create()]
 		pub fn create(origin) {
 			let sender = ensure_signed(origin)?;
 			let kitty_id = Self::next_kitty_id()?;
@@ -93,24 +267,68 @@ decl_module! {
 			let dna = Self::random_value(&sender);
 
 			// Create and store kitty
-			let kitty = Kitty(dna);
-			Self::insert_kitty(&sender, kitty_id, kitty);
+			let kitty = Self::new_kitty(dna, None, 0);
+			Self::insert_kitty(&sender, kitty_id, kitty)?;
 
 			Self::deposit_event(RawEvent::Created(sender, kitty_id));
 		}
 
 		/// Breed kitties
-		#[weight = 0]
+		#[weight = T::WeightInfo::breed()]
 		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
 
-			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
+			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2, &sender)?;
 
 			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
 		}
 
+		/// Offer a kitty as a sire for a fee
+		#[weight = T::WeightInfo::offer_sire()]
+		pub fn offer_sire(origin, kitty_id: T::KittyIndex, fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::RequireOwner);
+
+			<SireOffers<T>>::insert(kitty_id, fee);
+
+			Self::deposit_event(RawEvent::SireOffered(sender, kitty_id, fee));
+		}
+
+		/// Withdraw a sire offer
+		#[weight = T::WeightInfo::cancel_sire_offer()]
+		pub fn cancel_sire_offer(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::RequireOwner);
+			ensure!(<SireOffers<T>>::contains_key(kitty_id), Error::<T>::NotOfferedAsSire);
+
+			<SireOffers<T>>::remove(kitty_id);
+
+			Self::deposit_event(RawEvent::SireOfferCancelled(sender, kitty_id));
+		}
+
+		/// Pay the fee of an offered sire to breed it with one of your kitties.
+		/// The new kitty belongs to the payer.
+		#[weight = T::WeightInfo::breed_with_sire()]
+		#[transactional]
+		pub fn breed_with_sire(origin, matron_id: T::KittyIndex, sire_id: T::KittyIndex, max_fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let fee = Self::sire_offer(sire_id).ok_or(Error::<T>::NotOfferedAsSire)?;
+			ensure!(max_fee >= fee, Error::<T>::PriceTooLow);
+
+			let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			let new_kitty_id = Self::do_breed(&sender, matron_id, sire_id, &sire_owner)?;
+
+			T::Currency::transfer(&sender, &sire_owner, fee, ExistenceRequirement::KeepAlive)?;
+
+			Self::deposit_event(RawEvent::SireRented(sire_owner, sender, sire_id, matron_id, new_kitty_id, fee));
+		}
+
 		/// Transfer a kitty to new owner
-		#[weight = 0]
+		#[weight = T::WeightInfo::transfer()]
 		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
 
@@ -118,19 +336,83 @@ decl_module! {
 			//   ref: https://github.com/SubstrateCourse/kitties-course/issues/3
 			// ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
 			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), "Kitty is not owned by sender.");
+			Self::ensure_not_in_auction(kitty_id)?;
 
-			Self::do_transfer(&sender, &to, kitty_id);
+			Self::do_transfer(&sender, &to, kitty_id)?;
 
 			Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
 		}
 
+		/// Allow `spender` to transfer a kitty. None to clear the approval
+		#[weight = T::WeightInfo::approve()]
+		pub fn approve(origin, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(sender == owner || Self::is_approved_for_all(&owner, &sender), Error::<T>::RequireOwner);
+
+			<KittyApprovals<T>>::mutate_exists(kitty_id, |approval| *approval = spender.clone());
+
+			Self::deposit_event(RawEvent::Approval(owner, spender, kitty_id));
+		}
+
+		/// Allow or disallow `operator` to transfer every kitty of the sender
+		#[weight = T::WeightInfo::set_approval_for_all()]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+			let sender = ensure_signed(origin)?;
+
+			if approved {
+				<OperatorApprovals<T>>::insert(&sender, &operator, true);
+			} else {
+				<OperatorApprovals<T>>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+		}
+
+		/// Transfer a kitty of `from` as its approved spender or operator
+		#[weight = T::WeightInfo::transfer_from()]
+		pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(from.clone()), Error::<T>::RequireOwner);
+			ensure!(Self::is_approved_or_owner(&sender, &from, kitty_id), Error::<T>::NotApproved);
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			Self::do_transfer(&from, &to, kitty_id)?;
+
+			Self::deposit_event(RawEvent::Transferred(from, to, kitty_id));
+		}
+
+		/// Burn a kitty and get its deposit back
+		#[weight = T::WeightInfo::release()]
+		pub fn release(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::RequireOwner);
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			<OwnedKittiesList<T>>::remove(&sender, kitty_id);
+			<KittyOwners<T>>::remove(kitty_id);
+			<Kitties<T>>::remove(kitty_id);
+			<KittyPrices<T>>::remove(kitty_id);
+			<SireOffers<T>>::remove(kitty_id);
+			<KittyApprovals<T>>::remove(kitty_id);
+
+			let deposit = <KittyDeposits<T>>::take(kitty_id);
+			T::Currency::unreserve(&sender, deposit);
+
+			Self::deposit_event(RawEvent::Released(sender, kitty_id, deposit));
+		}
+
 		/// Set a price for a kitty for sale
 		/// None to delist the kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::ask()]
 		pub fn ask(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_not_in_auction(kitty_id)?;
 
 			<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
 
@@ -138,7 +420,8 @@ decl_module! {
 		}
 
 		/// Buy a kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::buy()]
+		#[transactional]
 		pub fn buy(origin, kitty_id: T::KittyIndex, price: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
 
@@ -152,10 +435,106 @@ decl_module! {
 
 			<KittyPrices<T>>::remove(kitty_id);
 
-			Self::do_transfer(&owner, &sender, kitty_id);
+			Self::do_transfer(&owner, &sender, kitty_id)?;
 
 			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price));
 		}
+
+		/// Put a kitty up for auction for `duration` blocks
+		#[weight = T::WeightInfo::create_auction()]
+		pub fn create_auction(origin, kitty_id: T::KittyIndex, kind: AuctionKind<BalanceOf<T>>, duration: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::RequireOwner);
+			Self::ensure_not_in_auction(kitty_id)?;
+			ensure!(!duration.is_zero(), Error::<T>::InvalidAuction);
+			if let AuctionKind::Dutch { start_price, end_price } = &kind {
+				ensure!(end_price <= start_price, Error::<T>::InvalidAuction);
+			}
+
+			let now = <system::Module<T>>::block_number();
+			let end = now.saturating_add(duration);
+			let ending = <AuctionsEndingAt<T>>::decode_len(end).unwrap_or(0) as u32;
+			ensure!(ending < T::MaxAuctionsPerBlock::get(), Error::<T>::TooManyAuctions);
+
+			// The auction replaces any fixed price
+			<KittyPrices<T>>::remove(kitty_id);
+			<Auctions<T>>::insert(kitty_id, Auction {
+				seller: sender.clone(),
+				kind,
+				start: now,
+				end,
+				highest_bid: None,
+			});
+			<AuctionsEndingAt<T>>::mutate(end, |kitty_ids| kitty_ids.push(kitty_id));
+
+			Self::deposit_event(RawEvent::AuctionCreated(sender, kitty_id, end));
+		}
+
+		/// Bid in an auction. English bids are reserved until outbid or the auction ends,
+		/// Dutch bids at or above the current price buy the kitty right away.
+		#[weight = T::WeightInfo::bid()]
+		#[transactional]
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::auction(kitty_id).ok_or(Error::<T>::NotInAuction)?;
+			let now = <system::Module<T>>::block_number();
+
+			ensure!(now <= auction.end, Error::<T>::AuctionEnded);
+			ensure!(sender != auction.seller, Error::<T>::SellerCannotBid);
+			ensure!(<OwnedKittiesList<T>>::len(&sender) < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKitties);
+
+			match auction.kind {
+				AuctionKind::English { min_bid } => {
+					ensure!(amount >= min_bid, Error::<T>::BidTooLow);
+					if let Some((_, highest)) = &auction.highest_bid {
+						ensure!(amount > *highest, Error::<T>::BidTooLow);
+					}
+
+					T::Currency::reserve(&sender, amount)?;
+
+					// Refund the outbid bidder
+					if let Some((bidder, highest)) = auction.highest_bid.take() {
+						T::Currency::unreserve(&bidder, highest);
+					}
+
+					auction.highest_bid = Some((sender.clone(), amount));
+					<Auctions<T>>::insert(kitty_id, auction);
+
+					Self::deposit_event(RawEvent::BidPlaced(sender, kitty_id, amount));
+				}
+				AuctionKind::Dutch { start_price, end_price } => {
+					let price = Self::dutch_price(start_price, end_price, auction.start, auction.end, now);
+					ensure!(amount >= price, Error::<T>::BidTooLow);
+
+					T::Currency::transfer(&sender, &auction.seller, price, ExistenceRequirement::KeepAlive)?;
+
+					<Auctions<T>>::remove(kitty_id);
+					Self::unschedule_auction(kitty_id, auction.end);
+
+					Self::do_transfer(&auction.seller, &sender, kitty_id)?;
+
+					Self::deposit_event(RawEvent::AuctionWon(auction.seller, sender, kitty_id, price));
+				}
+			}
+		}
+
+		/// Cancel an auction nobody has bid in yet
+		#[weight = T::WeightInfo::cancel_auction()]
+		pub fn cancel_auction(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auction(kitty_id).ok_or(Error::<T>::NotInAuction)?;
+
+			ensure!(auction.seller == sender, Error::<T>::RequireOwner);
+			ensure!(auction.highest_bid.is_none(), Error::<T>::AuctionHasBids);
+
+			<Auctions<T>>::remove(kitty_id);
+			Self::unschedule_auction(kitty_id, auction.end);
+
+			Self::deposit_event(RawEvent::AuctionCancelled(sender, kitty_id));
+		}
 	}
 }
 
@@ -186,25 +565,61 @@ impl<T: Trait> Module<T> {
 		<KittyOwners<T>>::insert(kitty_id, owner);
 	}
 
-	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty) {
+	/// Blocks a kitty of `generation` has to wait after breeding.
+	pub fn breeding_cooldown(generation: u16) -> T::BlockNumber {
+		T::BaseBreedingCooldown::get().saturating_mul((generation as u32 + 1).into())
+	}
+
+	fn new_kitty(dna: [u8; 16], parents: Option<(T::KittyIndex, T::KittyIndex)>, generation: u16) -> KittyOf<T> {
+		let now = <system::Module<T>>::block_number();
+		Kitty {
+			dna,
+			parents,
+			generation,
+			birth: now,
+			ready_at: now,
+		}
+	}
+
+	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: KittyOf<T>) -> DispatchResult {
+		ensure!(<OwnedKittiesList<T>>::len(owner) < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKitties);
+
+		let deposit = T::KittyDeposit::get();
+		T::Currency::reserve(owner, deposit)?;
+
 		// Create and store kitty
 		Kitties::<T>::insert(kitty_id, kitty.clone());
 		KittiesCount::<T>::put(kitty_id + 1.into());
+		<KittyDeposits<T>>::insert(kitty_id, deposit);
 		Self::insert_owned_kitty(owner, kitty_id);
+
+		Ok(())
 	}
 
-	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
-		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+	/// Breed `kitty_id_1` of `sender` with `kitty_id_2` of `sire_owner`. The new kitty
+	/// belongs to `sender`.
+	fn do_breed(
+		sender: &T::AccountId,
+		kitty_id_1: T::KittyIndex,
+		kitty_id_2: T::KittyIndex,
+		sire_owner: &T::AccountId,
+	) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let mut kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+		let mut kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
 		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_1))), Error::<T>::RequireOwner);
-		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_2))), Error::<T>::RequireOwner);
+		ensure!(<OwnedKitties<T>>::contains_key((&sire_owner, Some(kitty_id_2))), Error::<T>::RequireOwner);
 		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+		Self::ensure_not_in_auction(kitty_id_1)?;
+		Self::ensure_not_in_auction(kitty_id_2)?;
+
+		let now = <system::Module<T>>::block_number();
+		ensure!(kitty1.ready_at <= now && kitty2.ready_at <= now, Error::<T>::KittyOnCooldown);
 
 		let kitty_id = Self::next_kitty_id()?;
 
-		let kitty1_dna = kitty1.0;
-		let kitty2_dna = kitty2.0;
+		let kitty1_dna = kitty1.dna;
+		let kitty2_dna = kitty2.dna;
 
 		// Generate a random 128bit value
 		let selector = Self::random_value(&sender);
@@ -215,14 +630,228 @@ impl<T: Trait> Module<T> {
 			new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
 		}
 
-		Self::insert_kitty(sender, kitty_id, Kitty(new_dna));
+		let generation = kitty1.generation.max(kitty2.generation).saturating_add(1);
+		let kitty = Self::new_kitty(new_dna, Some((kitty_id_1, kitty_id_2)), generation);
+		Self::insert_kitty(sender, kitty_id, kitty)?;
+
+		// Parents rest before they can breed again
+		kitty1.ready_at = now.saturating_add(Self::breeding_cooldown(kitty1.generation));
+		kitty2.ready_at = now.saturating_add(Self::breeding_cooldown(kitty2.generation));
+		Kitties::<T>::insert(kitty_id_1, kitty1);
+		Kitties::<T>::insert(kitty_id_2, kitty2);
 
 		Ok(kitty_id)
 	}
 
-	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex)  {
+	/// Move `kitty_id` and its deposit from `from` to `to`. Nothing is changed if `to`
+	/// owns too many kitties or can't receive the deposit.
+	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(
+			from == to || <OwnedKittiesList<T>>::len(to) < T::MaxKittiesPerAccount::get(),
+			Error::<T>::TooManyKitties
+		);
+
+		// The deposit follows the kitty. Part of it is gone if `from` got slashed, only
+		// what actually moved is kept as the deposit.
+		let deposit = Self::kitty_deposit(kitty_id);
+		if !deposit.is_zero() {
+			let unmoved = T::Currency::repatriate_reserved(from, to, deposit, BalanceStatus::Reserved)?;
+			if !unmoved.is_zero() {
+				<KittyDeposits<T>>::insert(kitty_id, deposit.saturating_sub(unmoved));
+			}
+		}
+
+		// Approvals are given by the previous owner
+		if <KittyApprovals<T>>::take(kitty_id).is_some() {
+			Self::deposit_event(RawEvent::Approval(from.clone(), None, kitty_id));
+		}
+
+		<SireOffers<T>>::remove(kitty_id);
 		<OwnedKittiesList<T>>::remove(&from, kitty_id);
 		Self::insert_owned_kitty(&to, kitty_id);
+
+		Ok(())
+	}
+
+	/// Whether `who` can transfer `kitty_id` of `owner`: the owner itself, the approved
+	/// spender of the kitty or an operator of the owner.
+	pub fn is_approved_or_owner(who: &T::AccountId, owner: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
+		who == owner ||
+			Self::kitty_approval(kitty_id).as_ref() == Some(who) ||
+			Self::is_approved_for_all(owner, who)
+	}
+
+	fn ensure_not_in_auction(kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+		Ok(())
+	}
+
+	/// Price of a Dutch auction at block `now`, falling linearly from `start_price`
+	/// at `start` to `end_price` at `end`.
+	pub fn dutch_price(
+		start_price: BalanceOf<T>,
+		end_price: BalanceOf<T>,
+		start: T::BlockNumber,
+		end: T::BlockNumber,
+		now: T::BlockNumber,
+	) -> BalanceOf<T> {
+		let duration: BalanceOf<T> = end.saturating_sub(start).saturated_into::<u32>().into();
+		let elapsed: BalanceOf<T> = now.min(end).saturating_sub(start).saturated_into::<u32>().into();
+		if duration.is_zero() {
+			return end_price;
+		}
+
+		let drop = start_price.saturating_sub(end_price).saturating_mul(elapsed) / duration;
+		start_price.saturating_sub(drop)
+	}
+
+	/// Close the auction of `kitty_id` if it ends at `end`: the highest bid pays the
+	/// seller and takes the kitty, or the kitty stays with the seller. Returns the
+	/// weight consumed.
+	/// Free the slot of an auction closed before its end, so it no longer counts
+	/// against `MaxAuctionsPerBlock`.
+	fn unschedule_auction(kitty_id: T::KittyIndex, end: T::BlockNumber) {
+		<AuctionsEndingAt<T>>::mutate(end, |kitty_ids| kitty_ids.retain(|id| *id != kitty_id));
+	}
+
+	fn settle_auction(kitty_id: T::KittyIndex, end: T::BlockNumber) -> Weight {
+		// Cancelled, bought or relisted with another end since it was scheduled
+		let auction = match Self::auction(kitty_id) {
+			Some(auction) if auction.end == end => auction,
+			_ => return T::DbWeight::get().reads(1),
+		};
+
+		<Auctions<T>>::remove(kitty_id);
+
+		match auction.highest_bid {
+			Some((bidder, amount)) => {
+				match Self::do_transfer(&auction.seller, &bidder, kitty_id) {
+					Ok(()) => {
+						let unpaid = T::Currency::repatriate_reserved(&bidder, &auction.seller, amount, BalanceStatus::Free)
+							.unwrap_or(amount);
+						if !unpaid.is_zero() {
+							debug::warn!("Auction of kitty {:?} settled with {:?} unpaid", kitty_id, unpaid);
+						}
+
+						Self::deposit_event(RawEvent::AuctionWon(auction.seller, bidder, kitty_id, amount));
+					}
+					Err(e) => {
+						// The winner can't take the kitty, it stays with the seller
+						debug::warn!("Auction of kitty {:?} ended unsold: {:?}", kitty_id, e);
+						T::Currency::unreserve(&bidder, amount);
+
+						Self::deposit_event(RawEvent::AuctionEnded(auction.seller, kitty_id));
+					}
+				}
+
+				// The auction, both accounts twice, the deposit, the approval, the sire
+				// offer, both owned kitties lists and the owner
+				T::DbWeight::get().reads_writes(16, 16)
+			}
+			None => {
+				Self::deposit_event(RawEvent::AuctionEnded(auction.seller, kitty_id));
+				T::DbWeight::get().reads_writes(1, 1)
+			}
+		}
+	}
+
+	/// Everything about a kitty, as returned by the runtime API.
+	pub fn kitty_info(kitty_id: T::KittyIndex) -> Option<KittyInfoOf<T>> {
+		let kitty = Self::kitties(kitty_id)?;
+		let owner = Self::kitty_owner(kitty_id)?;
+
+		Some(KittyInfo {
+			id: kitty_id,
+			owner,
+			dna: kitty.dna,
+			traits: kitty.traits(),
+			price: Self::kitty_price(kitty_id),
+			parents: kitty.parents,
+			generation: kitty.generation,
+			birth: kitty.birth,
+			ready_at: kitty.ready_at,
+		})
+	}
+
+	/// Kitties of `owner`, at most `limit` (capped by `MAX_KITTIES_PAGE`) starting at `cursor`.
+	pub fn kitties_of(
+		owner: T::AccountId,
+		cursor: Option<T::KittyIndex>,
+		limit: u32,
+	) -> KittiesPage<T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber> {
+		let (ids, next) = <OwnedKittiesList<T>>::page(&owner, cursor, limit.min(MAX_KITTIES_PAGE));
+
+		KittiesPage {
+			kitties: ids.into_iter().filter_map(Self::kitty_info).collect(),
+			next,
+		}
+	}
+
+	/// All kitties for sale, cheapest first.
+	pub fn kitties_for_sale() -> Vec<KittyInfoOf<T>> {
+		let mut for_sale: Vec<(BalanceOf<T>, T::KittyIndex)> = <KittyPrices<T>>::iter()
+			.map(|(kitty_id, price)| (price, kitty_id))
+			.collect();
+		for_sale.sort();
+
+		for_sale.into_iter()
+			.filter_map(|(_, kitty_id)| Self::kitty_info(kitty_id))
+			.collect()
+	}
+
+	/// Turn the bare DNA kitties were stored as into `Kitty` records.
+	///
+	/// Parents recorded in the former `KittyParents` map are kept, generations start
+	/// at 0 and every kitty can breed right away. Only runs once.
+	fn migrate_kitty_records() -> Weight {
+		if Self::kitty_records_migrated() {
+			return T::DbWeight::get().reads(1);
+		}
+
+		Kitties::<T>::translate::<[u8; 16], _>(|kitty_id, dna| {
+			let parents = take_storage_item::<_, (T::KittyIndex, T::KittyIndex), Blake2_128Concat>(
+				b"Kitties", b"KittyParents", kitty_id,
+			);
+			Some(Kitty {
+				dna,
+				parents,
+				generation: 0,
+				birth: Zero::zero(),
+				ready_at: Zero::zero(),
+			})
+		});
+
+		KittyRecordsMigrated::put(true);
+		let translated: Weight = Self::kitties_count().saturated_into();
+		debug::info!("Migrated {} kitties to kitty records", translated);
+
+		T::DbWeight::get().reads_writes(1 + 2 * translated, 1 + 2 * translated)
+	}
+
+	/// Rebuild the owned kitties lists from `KittyOwners`.
+	///
+	/// The list used to store nothing, so chains started before it was implemented have
+	/// empty lists. Only runs once.
+	fn rebuild_owned_kitties() -> Weight {
+		if Self::owned_kitties_rebuilt() {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+		for (kitty_id, owner) in <KittyOwners<T>>::iter() {
+			reads += 2;
+			if !<OwnedKittiesList<T>>::contains(&owner, kitty_id) {
+				<OwnedKittiesList<T>>::append(&owner, kitty_id);
+				reads += 3;
+				writes += 4;
+			}
+		}
+
+		OwnedKittiesRebuilt::put(true);
+		debug::info!("Rebuilt owned kitties lists");
+
+		T::DbWeight::get().reads_writes(reads, writes)
 	}
 }
 
@@ -232,7 +861,11 @@ mod tests {
 	use super::*;
 
 	use sp_core::H256;
-	use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+	use frame_support::{
+		impl_outer_origin, parameter_types, weights::{Weight, constants::RocksDbWeight}, assert_ok, assert_noop,
+		StorageHasher,
+		traits::{OnInitialize, OnRuntimeUpgrade},
+	};
 	use sp_runtime::{
 		traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 	};
@@ -267,7 +900,7 @@ mod tests {
 		type Event = ();
 		type BlockHashCount = BlockHashCount;
 		type MaximumBlockWeight = MaximumBlockWeight;
-		type DbWeight = ();
+		type DbWeight = RocksDbWeight;
 		type BlockExecutionWeight = ();
 		type ExtrinsicBaseWeight = ();
 		type MaximumExtrinsicWeight = MaximumBlockWeight;
@@ -275,51 +908,960 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type Version = ();
 		type SystemWeightInfo = ();
-		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type PalletInfo = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
-		}
+	}
 
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+		pub const MaxLocks: u32 = 50;
+	}
+	impl pallet_balances::Trait for Test {
+		type MaxLocks = MaxLocks;
+		type Balance = u64;
+		type Event = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = system::Module<Test>;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const BaseBreedingCooldown: u64 = 10;
+		pub const KittyDeposit: u64 = 5;
+		pub const MaxKittiesPerAccount: u32 = 5;
+		pub const MaxAuctionsPerBlock: u32 = 2;
+	}
 	impl Trait for Test {
 		type Event = ();
-		type Currency: Balances;
-		type Randomness: Randomness<H256>;
 		type KittyIndex = u32;
+		type Currency = pallet_balances::Module<Test>;
+		type Randomness = pallet_randomness_collective_flip::Module<Test>;
+		type BaseBreedingCooldown = BaseBreedingCooldown;
+		type KittyDeposit = KittyDeposit;
+		type MaxKittiesPerAccount = MaxKittiesPerAccount;
+		type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+		type WeightInfo = ();
 	}
 	type OwnedKittiesTest = OwnedKitties<Test>;
+	type OwnedKittiesListTest = OwnedKittiesList<Test>;
+	type Kitties = Module<Test>;
+	type System = system::Module<Test>;
+	type Balances = pallet_balances::Module<Test>;
+
+	fn run_to_block(n: u64) {
+		while System::block_number() < n {
+			System::set_block_number(System::block_number() + 1);
+			Kitties::on_initialize(System::block_number());
+		}
+	}
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+
+		GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
+
+		t.into()
 	}
 
 	#[test]
 	fn owned_kitties_can_append_values() {
 		new_test_ext().execute_with(|| {
-			OwnedKittiesTest::append(&1);
+			OwnedKittiesListTest::append(&0, 1);
 
-			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem {
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem::<Test> {
 				prev: Some(1),
 				next: Some(1),
 			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(1))), Some(KittyLinkedItem::<Test> {
+				prev: None,
+				next: None,
+			}));
+
+			OwnedKittiesListTest::append(&0, 2);
+
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem::<Test> {
+				prev: Some(2),
+				next: Some(1),
+			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(1))), Some(KittyLinkedItem::<Test> {
+				prev: None,
+				next: Some(2),
+			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), Some(KittyLinkedItem::<Test> {
+				prev: Some(1),
+				next: None,
+			}));
+
+			assert_eq!(OwnedKittiesListTest::len(&0), 2);
+		});
+	}
+
+	#[test]
+	fn owned_kitties_ignores_duplicates() {
+		new_test_ext().execute_with(|| {
+			OwnedKittiesListTest::append(&0, 1);
+			OwnedKittiesListTest::append(&0, 1);
+
+			assert_eq!(OwnedKittiesListTest::len(&0), 1);
+			assert_eq!(OwnedKittiesListTest::iter(&0).collect::<Vec<_>>(), vec![1]);
 		});
 	}
 
 	#[test]
 	fn owned_kitties_can_remove_values() {
 		new_test_ext().execute_with(|| {
-			OwnedKittiesTest::append(1);
-			OwnedKittiesTest::append(2);
-			OwnedKittiesTest::append(3);
+			OwnedKittiesListTest::append(&0, 1);
+			OwnedKittiesListTest::append(&0, 2);
+			OwnedKittiesListTest::append(&0, 3);
 
-			OwnedKittiesTest::remove(2);
+			OwnedKittiesListTest::remove(&0, 2);
 
-			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem {
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem::<Test> {
 				prev: Some(3),
 				next: Some(1),
 			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(1))), Some(KittyLinkedItem::<Test> {
+				prev: None,
+				next: Some(3),
+			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), None);
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(3))), Some(KittyLinkedItem::<Test> {
+				prev: Some(1),
+				next: None,
+			}));
+
+			assert!(!OwnedKittiesListTest::contains(&0, 2));
+			assert_eq!(OwnedKittiesListTest::len(&0), 2);
+
+			// Removing a missing value is a no-op.
+			OwnedKittiesListTest::remove(&0, 2);
+			assert_eq!(OwnedKittiesListTest::len(&0), 2);
+
+			OwnedKittiesListTest::remove(&0, 1);
+			OwnedKittiesListTest::remove(&0, 3);
+
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem::<Test> {
+				prev: None,
+				next: None,
+			}));
+			assert_eq!(OwnedKittiesListTest::len(&0), 0);
+		});
+	}
+
+	#[test]
+	fn owned_kitties_can_iterate_both_ways() {
+		new_test_ext().execute_with(|| {
+			for value in 1..=5 {
+				OwnedKittiesListTest::append(&0, value);
+			}
+			OwnedKittiesListTest::append(&1, 9);
+
+			assert_eq!(OwnedKittiesListTest::iter(&0).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+			assert_eq!(OwnedKittiesListTest::iter(&0).rev().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1]);
+			assert_eq!(OwnedKittiesListTest::iter_from(&0, 3).collect::<Vec<_>>(), vec![3, 4, 5]);
+			assert_eq!(OwnedKittiesListTest::iter_from(&0, 9).count(), 0);
+			assert_eq!(OwnedKittiesListTest::iter(&2).count(), 0);
+
+			// Both ends meet in the middle.
+			let mut iter = OwnedKittiesListTest::iter(&0);
+			assert_eq!(iter.next(), Some(1));
+			assert_eq!(iter.next_back(), Some(5));
+			assert_eq!(iter.next(), Some(2));
+			assert_eq!(iter.next_back(), Some(4));
+			assert_eq!(iter.next(), Some(3));
+			assert_eq!(iter.next_back(), None);
+			assert_eq!(iter.next(), None);
+		});
+	}
+
+	#[test]
+	fn owned_kitties_can_be_paginated() {
+		new_test_ext().execute_with(|| {
+			for value in 1..=5 {
+				OwnedKittiesListTest::append(&0, value);
+			}
+
+			assert_eq!(OwnedKittiesListTest::page(&0, None, 2), (vec![1, 2], Some(3)));
+			assert_eq!(OwnedKittiesListTest::page(&0, Some(3), 2), (vec![3, 4], Some(5)));
+			assert_eq!(OwnedKittiesListTest::page(&0, Some(5), 2), (vec![5], None));
+			assert_eq!(OwnedKittiesListTest::page(&0, None, 10), (vec![1, 2, 3, 4, 5], None));
+			assert_eq!(OwnedKittiesListTest::page(&0, Some(9), 2), (vec![], None));
+		});
+	}
+
+	#[test]
+	fn transfer_moves_kitty_between_lists() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![1]);
+			assert_eq!(OwnedKittiesListTest::iter(&2).collect::<Vec<_>>(), vec![0]);
+			assert_eq!(Kitties::owned_kitties_count(1), 1);
+			assert_eq!(Kitties::owned_kitties_count(2), 1);
+		});
+	}
+
+	#[test]
+	fn rebuild_owned_kitties_works() {
+		new_test_ext().execute_with(|| {
+			// Ownership written while the list was a stub.
+			OwnedKittiesRebuilt::put(false);
+			<KittyOwners<Test>>::insert(0, 1);
+			<KittyOwners<Test>>::insert(1, 2);
+			<KittyOwners<Test>>::insert(2, 1);
+
+			Kitties::rebuild_owned_kitties();
+
+			let mut owned = OwnedKittiesListTest::iter(&1).collect::<Vec<_>>();
+			owned.sort();
+			assert_eq!(owned, vec![0, 2]);
+			assert_eq!(OwnedKittiesListTest::iter(&2).collect::<Vec<_>>(), vec![1]);
+			assert_eq!(OwnedKittiesListTest::len(&1), 2);
+			assert!(Kitties::owned_kitties_rebuilt());
+
+			// Only runs once.
+			<KittyOwners<Test>>::insert(3, 2);
+			Kitties::rebuild_owned_kitties();
+			assert!(!OwnedKittiesListTest::contains(&2, 3));
+		});
+	}
+
+	#[test]
+	fn kitties_of_is_paginated() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..3 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_ok!(Kitties::create(Origin::signed(2)));
+			assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+			assert_ok!(Kitties::ask(Origin::signed(1), 2, Some(10)));
+
+			let page = Kitties::kitties_of(1, None, 2);
+			assert_eq!(page.kitties.iter().map(|k| k.id).collect::<Vec<_>>(), vec![0, 1]);
+			assert_eq!(page.next, Some(2));
+
+			let page = Kitties::kitties_of(1, page.next, 2);
+			assert_eq!(page.kitties.iter().map(|k| k.id).collect::<Vec<_>>(), vec![2, 4]);
+			assert_eq!(page.next, None);
+
+			assert_eq!(page.kitties[0].price, Some(10));
+			assert_eq!(page.kitties[0].parents, None);
+			assert_eq!(page.kitties[1].owner, 1);
+			assert_eq!(page.kitties[1].parents, Some((0, 1)));
+			assert_eq!(page.kitties[1].dna, Kitties::kitties(4).unwrap().dna);
+			assert_eq!(page.kitties[1].generation, 1);
+		});
+	}
+
+	#[test]
+	fn kitties_for_sale_are_sorted_by_price() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..4 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(30)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 2, Some(10)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 3, Some(20)));
+
+			let for_sale = Kitties::kitties_for_sale();
+			assert_eq!(
+				for_sale.iter().map(|k| (k.id, k.price)).collect::<Vec<_>>(),
+				vec![(2, Some(10)), (3, Some(20)), (0, Some(30))]
+			);
+		});
+	}
+
+	#[test]
+	fn traits_are_decoded_from_dna() {
+		let mut dna = [0u8; 16];
+		dna[..5].copy_from_slice(&[16, 14, 45, 3, 255]);
+
+		assert_eq!(KittyTraits::from_dna(&dna), KittyTraits {
+			pattern: 1,
+			eyes: 14,
+			accessory: 5,
+			fur: 3,
+			mouth: 5,
+		});
+
+		// Only the expressed bytes matter.
+		dna[15] = 42;
+		assert_eq!(KittyTraits::from_dna(&dna).fur, 3);
+	}
+
+	#[test]
+	fn breed_records_parents_and_generation() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(5);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			let kitty = Kitties::kitties(0).unwrap();
+			assert_eq!(kitty.parents, None);
+			assert_eq!(kitty.generation, 0);
+			assert_eq!(kitty.birth, 5);
+
+			assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+			let child = Kitties::kitties(2).unwrap();
+			assert_eq!(child.parents, Some((0, 1)));
+			assert_eq!(child.generation, 1);
+			assert_eq!(child.birth, 5);
+			assert_eq!(child.ready_at, 5);
+
+			// Generation 0 parents rest the base cooldown.
+			assert_eq!(Kitties::kitties(0).unwrap().ready_at, 15);
+			assert_eq!(Kitties::kitties(1).unwrap().ready_at, 15);
+
+			System::set_block_number(15);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::breed(Origin::signed(1), 2, 3));
+
+			let grandchild = Kitties::kitties(4).unwrap();
+			assert_eq!(grandchild.generation, 2);
+			// Generation 1 rests twice as long.
+			assert_eq!(Kitties::kitties(2).unwrap().ready_at, 35);
+			assert_eq!(Kitties::kitties(3).unwrap().ready_at, 25);
+		});
+	}
+
+	#[test]
+	fn breed_requires_parents_off_cooldown() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			for _ in 0..3 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::KittyOnCooldown);
+			assert_noop!(Kitties::breed(Origin::signed(1), 2, 1), Error::<Test>::KittyOnCooldown);
+
+			System::set_block_number(10);
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::KittyOnCooldown);
+
+			System::set_block_number(11);
+			assert_ok!(Kitties::breed(Origin::signed(1), 0, 2));
+		});
+	}
+
+	#[test]
+	fn migrate_kitty_records_works() {
+		new_test_ext().execute_with(|| {
+			KittyRecordsMigrated::put(false);
+			let dna = [7u8; 16];
+			frame_support::storage::migration::put_storage_value(
+				b"Kitties", b"Kitties", &Blake2_128Concat::hash(&0u32.encode()), dna,
+			);
+			frame_support::storage::migration::put_storage_value(
+				b"Kitties", b"Kitties", &Blake2_128Concat::hash(&2u32.encode()), dna,
+			);
+			frame_support::storage::migration::put_storage_value(
+				b"Kitties", b"KittyParents", &Blake2_128Concat::hash(&2u32.encode()), (0u32, 1u32),
+			);
+			KittiesCount::<Test>::put(3);
+
+			Kitties::migrate_kitty_records();
+
+			assert_eq!(Kitties::kitties(0), Some(Kitty {
+				dna,
+				parents: None,
+				generation: 0,
+				birth: 0,
+				ready_at: 0,
+			}));
+			assert_eq!(Kitties::kitties(2).unwrap().parents, Some((0, 1)));
+			assert_eq!(
+				take_storage_item::<_, (u32, u32), Blake2_128Concat>(b"Kitties", b"KittyParents", 2u32),
+				None
+			);
+			assert!(Kitties::kitty_records_migrated());
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_keeps_kitties_of_new_chains() {
+		new_test_ext().execute_with(|| {
+			assert!(Kitties::kitty_records_migrated());
+			assert!(Kitties::owned_kitties_rebuilt());
+
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+			let child = Kitties::kitties(2).unwrap();
+
+			<Kitties as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+			assert_eq!(Kitties::kitties(2), Some(child));
+			assert_eq!(Kitties::kitties(2).unwrap().parents, Some((0, 1)));
+			assert_eq!(Kitties::kitties(2).unwrap().generation, 1);
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![0, 1, 2]);
+		});
+	}
+
+	#[test]
+	fn offer_sire_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_noop!(Kitties::offer_sire(Origin::signed(2), 0, 10), Error::<Test>::RequireOwner);
+			assert_noop!(Kitties::cancel_sire_offer(Origin::signed(1), 0), Error::<Test>::NotOfferedAsSire);
+
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 10));
+			assert_eq!(Kitties::sire_offer(0), Some(10));
+
+			assert_noop!(Kitties::cancel_sire_offer(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+			assert_ok!(Kitties::cancel_sire_offer(Origin::signed(1), 0));
+			assert_eq!(Kitties::sire_offer(0), None);
+		});
+	}
+
+	#[test]
+	fn breed_with_sire_works() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(2)));
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 10));
+
+			assert_noop!(
+				Kitties::breed_with_sire(Origin::signed(2), 1, 0, 9),
+				Error::<Test>::PriceTooLow
+			);
+
+			assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 10));
+
+			// The payer owns the offspring, the sire stays with its owner.
+			assert_eq!(Kitties::kitty_owner(2), Some(2));
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+			assert_eq!(Kitties::kitties(2).unwrap().parents, Some((1, 0)));
+			// Each kitty holds a deposit of 5
+			assert_eq!(Balances::free_balance(1), 105);
+			assert_eq!(Balances::free_balance(2), 80);
+
+			// Both parents rest, the offer stays.
+			assert_eq!(Kitties::sire_offer(0), Some(10));
+			assert_ok!(Kitties::create(Origin::signed(3)));
+			assert_noop!(
+				Kitties::breed_with_sire(Origin::signed(3), 3, 0, 10),
+				Error::<Test>::KittyOnCooldown
+			);
+		});
+	}
+
+	#[test]
+	fn breed_with_sire_checks_parents() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(2)));
+
+			assert_noop!(
+				Kitties::breed_with_sire(Origin::signed(2), 1, 0, 10),
+				Error::<Test>::NotOfferedAsSire
+			);
+
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 10));
+
+			// The matron must belong to the payer.
+			assert_noop!(
+				Kitties::breed_with_sire(Origin::signed(3), 1, 0, 10),
+				Error::<Test>::RequireOwner
+			);
+			assert_noop!(
+				Kitties::breed_with_sire(Origin::signed(1), 0, 0, 10),
+				Error::<Test>::RequireDifferentParent
+			);
+
+			// Nothing is paid when breeding fails.
+			assert_eq!(Balances::free_balance(3), 100);
+		});
+	}
+
+	#[test]
+	fn breed_with_sire_requires_funds() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(2)));
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 1_000));
+
+			assert!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 1_000).is_err());
+
+			// The failed payment leaves no offspring behind.
+			assert_eq!(Kitties::kitties_count(), 2);
+			assert_eq!(Kitties::kitty_owner(2), None);
+			assert_eq!(Kitties::kitties(1).unwrap().ready_at, 0);
+		});
+	}
+
+	#[test]
+	fn transfer_cancels_sire_offer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 10));
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(Kitties::sire_offer(0), None);
+		});
+	}
+
+	fn english(min_bid: u64) -> AuctionKind<u64> {
+		AuctionKind::English { min_bid }
+	}
+
+	#[test]
+	fn create_auction_works() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+
+			assert_noop!(Kitties::create_auction(Origin::signed(2), 0, english(5), 10), Error::<Test>::RequireOwner);
+			assert_noop!(Kitties::create_auction(Origin::signed(1), 0, english(5), 0), Error::<Test>::InvalidAuction);
+			assert_noop!(
+				Kitties::create_auction(Origin::signed(1), 0, AuctionKind::Dutch { start_price: 5, end_price: 10 }, 10),
+				Error::<Test>::InvalidAuction
+			);
+
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(5), 10));
+			assert_eq!(Kitties::auction(0).unwrap().end, 11);
+			assert_eq!(Kitties::auctions_ending_at(11), vec![0]);
+			assert_eq!(Kitties::kitty_price(0), None);
+
+			assert_noop!(Kitties::create_auction(Origin::signed(1), 0, english(5), 10), Error::<Test>::KittyInAuction);
+		});
+	}
+
+	#[test]
+	fn kitty_in_auction_is_locked() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(5), 10));
+
+			assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyInAuction);
+			assert_noop!(Kitties::ask(Origin::signed(1), 0, Some(10)), Error::<Test>::KittyInAuction);
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyInAuction);
+			assert_noop!(Kitties::breed(Origin::signed(1), 1, 0), Error::<Test>::KittyInAuction);
+
+			assert_ok!(Kitties::cancel_auction(Origin::signed(1), 0));
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn english_auction_refunds_outbid_bidders() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+
+			assert_noop!(Kitties::bid(Origin::signed(1), 0, 20), Error::<Test>::SellerCannotBid);
+			assert_noop!(Kitties::bid(Origin::signed(2), 0, 9), Error::<Test>::BidTooLow);
+
+			assert_ok!(Kitties::bid(Origin::signed(2), 0, 20));
+			assert_eq!(Balances::reserved_balance(2), 20);
+
+			assert_noop!(Kitties::bid(Origin::signed(3), 0, 20), Error::<Test>::BidTooLow);
+			assert_ok!(Kitties::bid(Origin::signed(3), 0, 30));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Balances::reserved_balance(3), 30);
+
+			assert_noop!(Kitties::cancel_auction(Origin::signed(1), 0), Error::<Test>::AuctionHasBids);
+
+			// Settled at the start of the block after the end block
+			run_to_block(6);
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+			run_to_block(7);
+
+			assert_eq!(Kitties::auction(0), None);
+			assert_eq!(Kitties::kitty_owner(0), Some(3));
+			// The winner also holds the kitty deposit now
+			assert_eq!(Balances::reserved_balance(3), 5);
+			assert_eq!(Balances::free_balance(3), 70);
+			assert_eq!(Balances::free_balance(1), 125);
+			assert_noop!(Kitties::bid(Origin::signed(2), 0, 40), Error::<Test>::NotInAuction);
+		});
+	}
+
+	#[test]
+	fn auction_without_bids_returns_to_seller() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+
+			run_to_block(7);
+
+			assert_eq!(Kitties::auction(0), None);
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn auctions_ending_in_a_block_are_capped() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			for _ in 0..3 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 1, english(10), 5));
+			assert_noop!(
+				Kitties::create_auction(Origin::signed(1), 2, english(10), 5),
+				Error::<Test>::TooManyAuctions
+			);
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 2, english(10), 6));
+
+			assert_ok!(Kitties::bid(Origin::signed(2), 0, 10));
+			System::set_block_number(7);
+			// The block list, one sold and one unsold auction
+			assert_eq!(
+				Kitties::on_initialize(7),
+				RocksDbWeight::get().reads_writes(1 + 16 + 1, 1 + 16 + 1)
+			);
+			assert_eq!(Kitties::kitty_owner(0), Some(2));
+			assert_eq!(Kitties::kitty_owner(1), Some(1));
+			assert_eq!(Kitties::auction(2).map(|a| a.end), Some(7));
+		});
+	}
+
+	#[test]
+	fn closed_auctions_free_their_slot() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(2)));
+
+			// More listings than the block has slots, all ending at block 6
+			for _ in 0..3 {
+				assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+				assert_ok!(Kitties::cancel_auction(Origin::signed(1), 0));
+			}
+			let dutch = AuctionKind::Dutch { start_price: 10, end_price: 10 };
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 1, dutch, 5));
+			assert_ok!(Kitties::bid(Origin::signed(3), 1, 10));
+			assert_eq!(Kitties::auctions_ending_at(6), vec![]);
+
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+			assert_ok!(Kitties::create_auction(Origin::signed(2), 2, english(10), 5));
+			assert_eq!(Kitties::auctions_ending_at(6), vec![0, 2]);
+		});
+	}
+
+	#[test]
+	fn dutch_auction_price_falls_linearly() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(
+				Origin::signed(1), 0, AuctionKind::Dutch { start_price: 50, end_price: 10 }, 10
+			));
+
+			assert_eq!(Kitties::dutch_price(50, 10, 1, 11, 1), 50);
+			assert_eq!(Kitties::dutch_price(50, 10, 1, 11, 6), 30);
+			assert_eq!(Kitties::dutch_price(50, 10, 1, 11, 11), 10);
+			assert_eq!(Kitties::dutch_price(50, 10, 1, 11, 20), 10);
+
+			run_to_block(6);
+			assert_noop!(Kitties::bid(Origin::signed(2), 0, 29), Error::<Test>::BidTooLow);
+
+			// Pays the current price, not the bid
+			assert_ok!(Kitties::bid(Origin::signed(2), 0, 40));
+			assert_eq!(Kitties::kitty_owner(0), Some(2));
+			assert_eq!(Balances::free_balance(2), 70);
+			assert_eq!(Balances::free_balance(1), 125);
+			assert_eq!(Kitties::auction(0), None);
+
+			// Nothing left to settle
+			run_to_block(12);
+			assert_eq!(Kitties::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn relisted_kitty_is_settled_at_its_new_end() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+			assert_ok!(Kitties::cancel_auction(Origin::signed(1), 0));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 10));
+			assert_ok!(Kitties::bid(Origin::signed(2), 0, 10));
+
+			run_to_block(7);
+			assert_eq!(Kitties::auction(0).map(|a| a.end), Some(11));
+
+			run_to_block(12);
+			assert_eq!(Kitties::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn kitty_deposit_follows_the_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_eq!(Kitties::kitty_deposit(0), 5);
+			assert_eq!(Balances::reserved_balance(1), 5);
+			assert_eq!(Balances::free_balance(1), 95);
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 5);
+			assert_eq!(Balances::free_balance(1), 95);
+			assert_eq!(Balances::free_balance(2), 100);
+		});
+	}
+
+	#[test]
+	fn create_requires_the_deposit() {
+		new_test_ext().execute_with(|| {
+			let _ = Balances::deposit_creating(&4, 3);
+			assert_noop!(
+				Kitties::create(Origin::signed(4)),
+				pallet_balances::Error::<Test>::InsufficientBalance
+			);
+		});
+	}
+
+	#[test]
+	fn release_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 10));
+
+			assert_noop!(Kitties::release(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+
+			assert_ok!(Kitties::release(Origin::signed(1), 0));
+			assert_eq!(Kitties::kitties(0), None);
+			assert_eq!(Kitties::kitty_owner(0), None);
+			assert_eq!(Kitties::kitty_price(0), None);
+			assert_eq!(Kitties::sire_offer(0), None);
+			assert_eq!(Kitties::kitty_deposit(0), 0);
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![1]);
+			assert_eq!(Balances::reserved_balance(1), 5);
+			assert_eq!(Balances::free_balance(1), 95);
+
+			// Ids are never reused
+			assert_eq!(Kitties::kitties_count(), 2);
+			assert_noop!(Kitties::release(Origin::signed(1), 0), Error::<Test>::RequireOwner);
+		});
+	}
+
+	#[test]
+	fn release_fails_in_auction() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+
+			assert_noop!(Kitties::release(Origin::signed(1), 0), Error::<Test>::KittyInAuction);
+		});
+	}
+
+	#[test]
+	fn kitties_per_account_are_capped() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..5 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::TooManyKitties);
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::TooManyKitties);
+
+			// Releasing one makes room again
+			assert_ok!(Kitties::release(Origin::signed(1), 4));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+		});
+	}
+
+	#[test]
+	fn kitties_received_are_capped() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			for _ in 0..5 {
+				assert_ok!(Kitties::create(Origin::signed(2)));
+			}
+			for _ in 0..4 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+
+			assert_noop!(Kitties::transfer(Origin::signed(1), 2, 5), Error::<Test>::TooManyKitties);
+
+			assert_ok!(Kitties::approve(Origin::signed(1), 5, Some(3)));
+			assert_noop!(Kitties::transfer_from(Origin::signed(3), 1, 2, 5), Error::<Test>::TooManyKitties);
+
+			assert_ok!(Kitties::ask(Origin::signed(1), 5, Some(10)));
+			assert_noop!(Kitties::buy(Origin::signed(2), 5, 10), Error::<Test>::TooManyKitties);
+
+			assert_ok!(Kitties::create_auction(
+				Origin::signed(1), 6, AuctionKind::Dutch { start_price: 10, end_price: 10 }, 5
+			));
+			assert_noop!(Kitties::bid(Origin::signed(2), 6, 10), Error::<Test>::TooManyKitties);
+
+			// Sires are bred into the payer's list
+			assert_ok!(Kitties::offer_sire(Origin::signed(1), 7, 10));
+			assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 0, 7, 10), Error::<Test>::TooManyKitties);
+
+			// Moving a kitty within the same list is not an addition
+			assert_ok!(Kitties::transfer(Origin::signed(2), 2, 0));
+			assert_eq!(Kitties::owned_kitties_count(2), 5);
+		});
+	}
+
+	#[test]
+	fn auction_won_over_the_cap_returns_to_seller() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+			assert_ok!(Kitties::bid(Origin::signed(2), 0, 20));
+
+			// The bidder fills its list before the auction ends
+			for _ in 0..5 {
+				assert_ok!(Kitties::create(Origin::signed(2)));
+			}
+			run_to_block(7);
+
+			assert_eq!(Kitties::auction(0), None);
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+			assert_eq!(Balances::reserved_balance(2), 25);
+			assert_eq!(Balances::free_balance(2), 75);
+			assert_eq!(Balances::free_balance(1), 95);
+		});
+	}
+
+	#[test]
+	fn transfer_keeps_the_deposit_actually_moved() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			let _ = Balances::slash_reserved(&1, 2);
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(Kitties::kitty_deposit(0), 3);
+			assert_eq!(Balances::reserved_balance(2), 3);
+
+			assert_ok!(Kitties::release(Origin::signed(2), 0));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 103);
+		});
+	}
+
+	#[test]
+	fn transfer_fails_when_the_deposit_cannot_move() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			// Account 4 does not exist, it can't hold a reserved deposit
+			assert_noop!(
+				Kitties::transfer(Origin::signed(1), 4, 0),
+				pallet_balances::Error::<Test>::DeadAccount
+			);
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+		});
+	}
+
+	#[test]
+	fn approved_spender_can_transfer_from() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_noop!(Kitties::approve(Origin::signed(2), 0, Some(2)), Error::<Test>::RequireOwner);
+			assert_noop!(Kitties::approve(Origin::signed(1), 1, Some(2)), Error::<Test>::InvalidKittyId);
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 1, 3, 0), Error::<Test>::NotApproved);
+
+			assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(2)));
+			assert_eq!(Kitties::kitty_approval(0), Some(2));
+
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 3, 2, 0), Error::<Test>::RequireOwner);
+			assert_ok!(Kitties::transfer_from(Origin::signed(2), 1, 3, 0));
+			assert_eq!(Kitties::kitty_owner(0), Some(3));
+			assert_eq!(OwnedKittiesListTest::iter(&3).collect::<Vec<_>>(), vec![0]);
+
+			// The approval does not survive the transfer
+			assert_eq!(Kitties::kitty_approval(0), None);
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 3, 2, 0), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn approval_can_be_cleared() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(2)));
+			assert_ok!(Kitties::approve(Origin::signed(1), 0, None));
+
+			assert_eq!(Kitties::kitty_approval(0), None);
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 1, 2, 0), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn operator_can_approve_and_transfer_every_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 2, true));
+			assert!(Kitties::is_approved_for_all(1, 2));
+
+			assert_ok!(Kitties::approve(Origin::signed(2), 0, Some(3)));
+			assert_ok!(Kitties::transfer_from(Origin::signed(3), 1, 3, 0));
+			assert_ok!(Kitties::transfer_from(Origin::signed(2), 1, 2, 1));
+			assert_eq!(Kitties::kitty_owner(0), Some(3));
+			assert_eq!(Kitties::kitty_owner(1), Some(2));
+
+			// Operators of the previous owner have no rights on the new owner's kitties
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 3, 2, 0), Error::<Test>::NotApproved);
+
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 2, false));
+			assert!(!Kitties::is_approved_for_all(1, 2));
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 1, 2, 2), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn sale_clears_approval() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(3)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(Kitties::buy(Origin::signed(2), 0, 10));
+
+			assert_eq!(Kitties::kitty_approval(0), None);
+			assert_noop!(Kitties::transfer_from(Origin::signed(3), 2, 3, 0), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn approved_spender_cannot_move_kitty_in_auction() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(2)));
+			assert_ok!(Kitties::create_auction(Origin::signed(1), 0, english(10), 5));
+
+			assert_noop!(Kitties::transfer_from(Origin::signed(2), 1, 2, 0), Error::<Test>::KittyInAuction);
 		});
 	}
 }
@@ -1,7 +1,10 @@
 use frame_support::{StorageMap, Parameter};
 use sp_runtime::traits::Member;
+use sp_std::{marker::PhantomData, prelude::*};
 use codec::{Encode, Decode};
 
+/// A node of the list. The item stored under `(key, None)` is the head:
+/// its `next` is the first value and its `prev` is the last value.
 #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
 #[derive(Encode, Decode)]
 pub struct LinkedItem<Value> {
@@ -9,12 +12,15 @@ pub struct LinkedItem<Value> {
 	pub next: Option<Value>,
 }
 
-pub struct LinkedList<Storage, Key, Value>(sp_std::marker::PhantomData<(Storage, Key, Value)>);
+/// A doubly linked list of `Value`s per `Key`, kept in `Storage`, with the number of
+/// values of each list kept in `Length`.
+pub struct LinkedList<Storage, Length, Key, Value>(PhantomData<(Storage, Length, Key, Value)>);
 
-impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
+impl<Storage, Length, Key, Value> LinkedList<Storage, Length, Key, Value> where
 	Value: Parameter + Member + Copy,
 	Key: Parameter,
 	Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+	Length: StorageMap<Key, u32, Query = u32>,
 {
 	fn read_head(key: &Key) -> LinkedItem<Value> {
 		Self::read(key, None)
@@ -35,11 +41,161 @@ impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
 		Storage::insert((&key, value), item);
 	}
 
+	/// Add `value` at the end of the list of `key`. Does nothing if it is already in it.
 	pub fn append(key: &Key, value: Value) {
-		// 作业
+		if Self::contains(key, value) {
+			return;
+		}
+
+		let head = Self::read_head(key);
+		let new_head = LinkedItem {
+			prev: Some(value),
+			next: head.next,
+		};
+
+		Self::write_head(key, new_head);
+
+		let prev = Self::read(key, head.prev);
+		let new_prev = LinkedItem {
+			prev: prev.prev,
+			next: Some(value),
+		};
+		Self::write(key, head.prev, new_prev);
+
+		let item = LinkedItem {
+			prev: head.prev,
+			next: None,
+		};
+		Self::write(key, Some(value), item);
+
+		Length::mutate(key, |len| *len += 1);
 	}
 
+	/// Take `value` out of the list of `key`. Does nothing if it is not in it.
 	pub fn remove(key: &Key, value: Value) {
-		// 作业
+		// take = get + remove
+		if let Some(item) = Storage::take((&key, Some(value))) {
+			let prev = Self::read(key, item.prev);
+			let new_prev = LinkedItem {
+				prev: prev.prev,
+				next: item.next,
+			};
+
+			Self::write(key, item.prev, new_prev);
+
+			let next = Self::read(key, item.next);
+			let new_next = LinkedItem {
+				prev: item.prev,
+				next: next.next,
+			};
+
+			Self::write(key, item.next, new_next);
+
+			Length::mutate(key, |len| *len = len.saturating_sub(1));
+		}
+	}
+
+	/// Whether `value` is in the list of `key`.
+	pub fn contains(key: &Key, value: Value) -> bool {
+		Storage::contains_key((&key, Some(value)))
+	}
+
+	/// Number of values in the list of `key`.
+	pub fn len(key: &Key) -> u32 {
+		Length::get(key)
+	}
+
+	/// Iterate over the list of `key`, from the first to the last value or backwards.
+	pub fn iter(key: &Key) -> LinkedListIter<Storage, Key, Value> {
+		let head = Self::read_head(key);
+		LinkedListIter::new(key.clone(), head.next, head.prev)
+	}
+
+	/// Iterate over the list of `key`, from `value` to the last value. Empty if `value`
+	/// is not in the list.
+	pub fn iter_from(key: &Key, value: Value) -> LinkedListIter<Storage, Key, Value> {
+		if Self::contains(key, value) {
+			LinkedListIter::new(key.clone(), Some(value), Self::read_head(key).prev)
+		} else {
+			LinkedListIter::new(key.clone(), None, None)
+		}
+	}
+
+	/// Read at most `limit` values of the list of `key`, starting at `cursor` or at the
+	/// first value when `cursor` is `None`.
+	///
+	/// Returns the values and the cursor of the next page, `None` once the list is exhausted.
+	pub fn page(key: &Key, cursor: Option<Value>, limit: u32) -> (Vec<Value>, Option<Value>) {
+		let mut iter = match cursor {
+			Some(value) => Self::iter_from(key, value),
+			None => Self::iter(key),
+		};
+
+		let values: Vec<Value> = iter.by_ref().take(limit as usize).collect();
+		(values, iter.peek())
+	}
+}
+
+/// Iterator over a `LinkedList`. Front and back meet in the middle, so it can be
+/// consumed from both ends.
+pub struct LinkedListIter<Storage, Key, Value> {
+	key: Key,
+	front: Option<Value>,
+	back: Option<Value>,
+	_marker: PhantomData<Storage>,
+}
+
+impl<Storage, Key, Value> LinkedListIter<Storage, Key, Value> where
+	Value: Parameter + Member + Copy,
+	Key: Parameter,
+	Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+{
+	fn new(key: Key, front: Option<Value>, back: Option<Value>) -> Self {
+		LinkedListIter { key, front, back, _marker: PhantomData }
+	}
+
+	fn item(&self, value: Value) -> Option<LinkedItem<Value>> {
+		Storage::get((&self.key, Some(value)))
+	}
+
+	/// The value `next` would return, without advancing.
+	pub fn peek(&self) -> Option<Value> {
+		self.front
+	}
+}
+
+impl<Storage, Key, Value> Iterator for LinkedListIter<Storage, Key, Value> where
+	Value: Parameter + Member + Copy,
+	Key: Parameter,
+	Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+{
+	type Item = Value;
+
+	fn next(&mut self) -> Option<Value> {
+		let value = self.front?;
+		if Some(value) == self.back {
+			self.front = None;
+			self.back = None;
+		} else {
+			self.front = self.item(value).and_then(|item| item.next);
+		}
+		Some(value)
+	}
+}
+
+impl<Storage, Key, Value> DoubleEndedIterator for LinkedListIter<Storage, Key, Value> where
+	Value: Parameter + Member + Copy,
+	Key: Parameter,
+	Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+{
+	fn next_back(&mut self) -> Option<Value> {
+		let value = self.back?;
+		if Some(value) == self.front {
+			self.front = None;
+			self.back = None;
+		} else {
+			self.back = self.item(value).and_then(|item| item.prev);
+		}
+		Some(value)
 	}
 }
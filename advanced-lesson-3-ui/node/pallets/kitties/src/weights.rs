@@ -0,0 +1,146 @@
+//! Weights for pallet_kitties
+//!
+//! Only the storage reads and writes of each call are charged, counted by hand from the
+//! worst case set up in `benchmarking.rs`. Execution time is not included until the
+//! weights are generated from a benchmark run: build the node with
+//! `--features runtime-benchmarks` and run the command below.
+//!
+//! No weight depends on the length of the owned kitties lists. They are linked lists
+//! with a stored length, so appending, removing and counting touch the same number of
+//! items whatever the length, and the benchmarks no longer vary it.
+
+// ./target/release/node-template
+// benchmark
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_kitties
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/kitties/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn breed() -> Weight;
+	fn offer_sire() -> Weight;
+	fn cancel_sire_offer() -> Weight;
+	fn breed_with_sire() -> Weight;
+	fn transfer() -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn release() -> Weight;
+	fn ask() -> Weight;
+	fn buy() -> Weight;
+	fn create_auction() -> Weight;
+	fn bid() -> Weight;
+	fn cancel_auction() -> Weight;
+}
+
+/// Weights for pallet_kitties using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn create() -> Weight {
+		T::DbWeight::get().reads_writes(6 as Weight, 8 as Weight)
+	}
+	fn breed() -> Weight {
+		T::DbWeight::get().reads_writes(11 as Weight, 10 as Weight)
+	}
+	fn offer_sire() -> Weight {
+		T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+	}
+	fn cancel_sire_offer() -> Weight {
+		T::DbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn breed_with_sire() -> Weight {
+		T::DbWeight::get().reads_writes(14 as Weight, 12 as Weight)
+	}
+	fn transfer() -> Weight {
+		T::DbWeight::get().reads_writes(15 as Weight, 13 as Weight)
+	}
+	fn approve() -> Weight {
+		T::DbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn set_approval_for_all() -> Weight {
+		T::DbWeight::get().writes(1 as Weight)
+	}
+	fn transfer_from() -> Weight {
+		T::DbWeight::get().reads_writes(16 as Weight, 13 as Weight)
+	}
+	fn release() -> Weight {
+		T::DbWeight::get().reads_writes(8 as Weight, 11 as Weight)
+	}
+	fn ask() -> Weight {
+		T::DbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn buy() -> Weight {
+		T::DbWeight::get().reads_writes(16 as Weight, 14 as Weight)
+	}
+	fn create_auction() -> Weight {
+		T::DbWeight::get().reads_writes(3 as Weight, 3 as Weight)
+	}
+	fn bid() -> Weight {
+		T::DbWeight::get().reads_writes(17 as Weight, 18 as Weight)
+	}
+	fn cancel_auction() -> Weight {
+		T::DbWeight::get().reads_writes(2 as Weight, 2 as Weight)
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create() -> Weight {
+		RocksDbWeight::get().reads_writes(6 as Weight, 8 as Weight)
+	}
+	fn breed() -> Weight {
+		RocksDbWeight::get().reads_writes(11 as Weight, 10 as Weight)
+	}
+	fn offer_sire() -> Weight {
+		RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+	}
+	fn cancel_sire_offer() -> Weight {
+		RocksDbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn breed_with_sire() -> Weight {
+		RocksDbWeight::get().reads_writes(14 as Weight, 12 as Weight)
+	}
+	fn transfer() -> Weight {
+		RocksDbWeight::get().reads_writes(15 as Weight, 13 as Weight)
+	}
+	fn approve() -> Weight {
+		RocksDbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn set_approval_for_all() -> Weight {
+		RocksDbWeight::get().writes(1 as Weight)
+	}
+	fn transfer_from() -> Weight {
+		RocksDbWeight::get().reads_writes(16 as Weight, 13 as Weight)
+	}
+	fn release() -> Weight {
+		RocksDbWeight::get().reads_writes(8 as Weight, 11 as Weight)
+	}
+	fn ask() -> Weight {
+		RocksDbWeight::get().reads_writes(2 as Weight, 1 as Weight)
+	}
+	fn buy() -> Weight {
+		RocksDbWeight::get().reads_writes(16 as Weight, 14 as Weight)
+	}
+	fn create_auction() -> Weight {
+		RocksDbWeight::get().reads_writes(3 as Weight, 3 as Weight)
+	}
+	fn bid() -> Weight {
+		RocksDbWeight::get().reads_writes(17 as Weight, 18 as Weight)
+	}
+	fn cancel_auction() -> Weight {
+		RocksDbWeight::get().reads_writes(2 as Weight, 2 as Weight)
+	}
+}
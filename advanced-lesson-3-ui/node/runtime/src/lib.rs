@@ -62,6 +62,9 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -268,12 +271,24 @@ impl pallet_template::Trait for Runtime {
 }
 
 
+parameter_types! {
+	pub const BaseBreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const KittyDeposit: Balance = 1_000;
+	pub const MaxKittiesPerAccount: u32 = 100;
+	pub const MaxAuctionsPerBlock: u32 = 50;
+}
+
 /// Configure the template pallet in pallets/template.
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
 	type Randomness = RandomnessCollectiveFlip;
+	type BaseBreedingCooldown = BaseBreedingCooldown;
+	type KittyDeposit = KittyDeposit;
+	type MaxKittiesPerAccount = MaxKittiesPerAccount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -293,7 +308,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
-		KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{Module, Call, Storage, Config, Event<T>},
 	}
 );
 
@@ -454,6 +469,20 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex, Balance, BlockNumber> for Runtime {
+		fn kitties_of(
+			owner: AccountId,
+			cursor: Option<KittyIndex>,
+			limit: u32,
+		) -> pallet_kitties_rpc_runtime_api::KittiesPage<AccountId, KittyIndex, Balance, BlockNumber> {
+			KittiesModule::kitties_of(owner, cursor, limit)
+		}
+
+		fn kitties_for_sale() -> Vec<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, KittyIndex, Balance, BlockNumber>> {
+			KittiesModule::kitties_for_sale()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -483,6 +512,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_kitties, KittiesModule);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
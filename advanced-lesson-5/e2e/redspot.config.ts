@@ -0,0 +1,25 @@
+import { RedspotUserConfig } from 'redspot/types';
+import '@redspot/patract';
+import '@redspot/chai';
+
+// The tests deploy `../erc20` and `../vesting` to a local contracts node, e.g.
+// `canvas --dev --tmp`.
+export default {
+  defaultNetwork: 'development',
+  contract: {
+    ink: {
+      toolchain: 'nightly',
+      sources: ['../erc20', '../vesting']
+    }
+  },
+  networks: {
+    development: {
+      endpoint: 'ws://127.0.0.1:9944',
+      types: {},
+      gasLimit: '400000000000'
+    }
+  },
+  mocha: {
+    timeout: 120000
+  }
+} as RedspotUserConfig;
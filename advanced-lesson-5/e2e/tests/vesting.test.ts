@@ -0,0 +1,79 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+const { api, getSigners } = network;
+
+// What the unit tests in `../vesting` can't cover: the vesting contract moving
+// tokens of a deployed `Erc20` with `transfer_from` and `transfer`.
+describe('Vesting', () => {
+  after(() => api.disconnect());
+
+  const blockNumber = async () => (await api.rpc.chain.getHeader()).number.toNumber();
+
+  async function waitUntil (block: number) {
+    while (await blockNumber() < block) {
+      await new Promise(resolve => setTimeout(resolve, 1000));
+    }
+  }
+
+  async function setup () {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals));
+    const [alice] = await getSigners();
+    const owner = await getRandomSigner(alice, one.muln(10000));
+    const beneficiary = await getRandomSigner(alice, one.muln(100));
+
+    const erc20Factory = await getContractFactory('erc20', owner);
+    const token = await erc20Factory.deploy('new', 1000000);
+    const vestingFactory = await getContractFactory('vesting', owner);
+    const vesting = await vestingFactory.deploy('new', token.address);
+
+    return { owner, beneficiary, token, vesting };
+  }
+
+  it('takes the schedule from the owner and releases it to the beneficiary', async () => {
+    const { owner, beneficiary, token, vesting } = await setup();
+    await token.tx.approve(vesting.address, 1000);
+
+    const start = await blockNumber();
+    await expect(() => vesting.tx.addSchedule(beneficiary.address, 1000, start, start, 2, false))
+      .to.changeTokenBalances(token, [owner, vesting], [-1000, 1000]);
+    expect((await token.query.allowance(owner.address, vesting.address)).output).to.equal(0);
+
+    await waitUntil(start + 2);
+    await expect(() => vesting.connect(beneficiary).tx.release())
+      .to.changeTokenBalances(token, [vesting, beneficiary], [-1000, 1000]);
+    expect((await vesting.query.releasableAmount(beneficiary.address)).output).to.equal(0);
+
+    // The finished schedule is replaced by a new one.
+    await token.tx.approve(vesting.address, 500);
+    await expect(() => vesting.tx.addSchedule(beneficiary.address, 500, start, start, 2, false))
+      .to.changeTokenBalance(token, vesting, 500);
+  });
+
+  it('refunds the unvested tokens on revoke', async () => {
+    const { owner, beneficiary, token, vesting } = await setup();
+    await token.tx.approve(vesting.address, 1000);
+
+    // Nothing vests before the cliff, so all of it goes back.
+    const start = await blockNumber();
+    await vesting.tx.addSchedule(beneficiary.address, 1000, start, start + 1000, 2000, true);
+    await expect(() => vesting.tx.revoke(beneficiary.address))
+      .to.changeTokenBalances(token, [vesting, owner], [-1000, 1000]);
+
+    expect((await vesting.query.releasableAmount(beneficiary.address)).output).to.equal(0);
+  });
+
+  it('keeps the schedule when the token transfer fails', async () => {
+    const { beneficiary, token, vesting } = await setup();
+
+    // No allowance, so `transfer_from` fails and no schedule is added.
+    const start = await blockNumber();
+    const result = await vesting.query.addSchedule(beneficiary.address, 1000, start, start, 2, false);
+    expect(result.output?.toJSON()).to.deep.equal({ Err: { Token: 'InsufficientAllowance' } });
+    await vesting.tx.addSchedule(beneficiary.address, 1000, start, start, 2, false);
+    expect((await vesting.query.scheduleOf(beneficiary.address)).output?.isNone).to.equal(true);
+    expect((await token.query.balanceOf(vesting.address)).output).to.equal(0);
+  });
+});
@@ -0,0 +1,501 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+/// Many tokens in one contract. A token created with a supply of 1 and a fixed
+/// supply is non-fungible, any other one is fungible.
+#[ink::contract]
+mod erc1155 {
+
+    use ink_storage::collections::HashMap as StorageHashMap;
+    use ink_prelude::vec::Vec;
+
+    pub type TokenId = u128;
+
+    #[ink(storage)]
+    pub struct Erc1155 {
+        balances: StorageHashMap<(TokenId, AccountId), Balance>,
+        /// Operators allowed to move all the tokens of an owner, keyed by (owner, operator).
+        operator_approvals: StorageHashMap<(AccountId, AccountId), bool>,
+        total_supply: StorageHashMap<TokenId, Balance>,
+        /// The only account allowed to mint more of a token.
+        creators: StorageHashMap<TokenId, AccountId>,
+        /// Tokens nobody can mint more of, e.g. non-fungible ones.
+        fixed_supply: StorageHashMap<TokenId, bool>,
+        next_token_id: TokenId,
+    }
+
+    /// `from` is None for mints.
+    #[ink(event)]
+    pub struct TransferSingle {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: AccountId,
+        id: TokenId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TransferBatch {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        ids: Vec<TokenId>,
+        values: Vec<Balance>,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        InSufficientBalance,
+        /// The caller is neither the owner nor one of its operators.
+        NotApproved,
+        UnknownToken,
+        /// Only the creator of a token can mint more of it.
+        NotCreator,
+        /// The token was created with a fixed supply.
+        FixedSupply,
+        /// `ids` and `values` (or `owners` and `ids`) have different lengths.
+        LengthMismatch,
+        SelfApproval,
+        /// The supply of a token or the token ids would exceed their maximum.
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc1155 {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                balances: StorageHashMap::new(),
+                operator_approvals: StorageHashMap::new(),
+                total_supply: StorageHashMap::new(),
+                creators: StorageHashMap::new(),
+                fixed_supply: StorageHashMap::new(),
+                next_token_id: 0,
+            }
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId, id: TokenId) -> Balance {
+            *self.balances.get(&(id, owner)).unwrap_or(&0)
+        }
+
+        /// The balance of `owners[i]` in `ids[i]`, for each `i`.
+        #[ink(message)]
+        pub fn balance_of_batch(&self, owners: Vec<AccountId>, ids: Vec<TokenId>) -> Result<Vec<Balance>> {
+            if owners.len() != ids.len() {
+                return Err(Error::LengthMismatch);
+            }
+            Ok(owners.into_iter().zip(ids).map(|(owner, id)| self.balance_of(owner, id)).collect())
+        }
+
+        #[ink(message)]
+        pub fn total_supply(&self, id: TokenId) -> Balance {
+            *self.total_supply.get(&id).unwrap_or(&0)
+        }
+
+        #[ink(message)]
+        pub fn creator_of(&self, id: TokenId) -> Option<AccountId> {
+            self.creators.get(&id).copied()
+        }
+
+        #[ink(message)]
+        pub fn is_fixed_supply(&self, id: TokenId) -> bool {
+            *self.fixed_supply.get(&id).unwrap_or(&false)
+        }
+
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            *self.operator_approvals.get(&(owner, operator)).unwrap_or(&false)
+        }
+
+        /// Let `operator` move all the caller's tokens, or stop it from doing so.
+        #[ink(message)]
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            let owner = Self::env().caller();
+            if owner == operator {
+                return Err(Error::SelfApproval);
+            }
+            if approved {
+                self.operator_approvals.insert((owner, operator), true);
+            } else {
+                self.operator_approvals.take(&(owner, operator));
+            }
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved
+            });
+            Ok(())
+        }
+
+        /// Create a new token with `initial_supply` owned by the caller, who becomes its creator.
+        /// With `fixed_supply` nobody can mint more of it, so a supply of 1 makes it non-fungible.
+        #[ink(message)]
+        pub fn create(&mut self, initial_supply: Balance, fixed_supply: bool) -> Result<TokenId> {
+            let creator = Self::env().caller();
+            let id = self.next_token_id;
+            self.next_token_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.creators.insert(id, creator);
+            if fixed_supply {
+                self.fixed_supply.insert(id, true);
+            }
+            self._mint(creator, creator, id, initial_supply)?;
+            Ok(id)
+        }
+
+        /// Create `value` more of token `id` for `to`. Creator only, unless the supply is fixed.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, id: TokenId, value: Balance) -> Result<()> {
+            let caller = Self::env().caller();
+            match self.creator_of(id) {
+                None => return Err(Error::UnknownToken),
+                Some(creator) if creator != caller => return Err(Error::NotCreator),
+                _ => {}
+            }
+            if self.is_fixed_supply(id) {
+                return Err(Error::FixedSupply);
+            }
+            self._mint(caller, to, id, value)
+        }
+
+        /// Move `value` of token `id` from `from` to `to`. The caller must be `from` or one of
+        /// its operators. `data` is ignored, there is no receiver hook.
+        #[ink(message)]
+        pub fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let _ = data;
+            let operator = self.ensure_approved(from)?;
+            let from_balance = self.balance_of(from, id)
+                .checked_sub(value)
+                .ok_or(Error::InSufficientBalance)?;
+            self._move(from, from_balance, to, id, value);
+
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: Some(from),
+                to,
+                id,
+                value
+            });
+            Ok(())
+        }
+
+        /// Move `values[i]` of token `ids[i]` from `from` to `to`, for each `i`. Nothing moves
+        /// unless every transfer can be made.
+        #[ink(message)]
+        pub fn safe_batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            ids: Vec<TokenId>,
+            values: Vec<Balance>,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let _ = data;
+            let operator = self.ensure_approved(from)?;
+            if ids.len() != values.len() {
+                return Err(Error::LengthMismatch);
+            }
+
+            // A repeated id is debited once per entry, so check the summed values of every id before moving any.
+            let mut totals: Vec<(TokenId, Balance)> = Vec::new();
+            for (&id, &value) in ids.iter().zip(values.iter()) {
+                match totals.iter_mut().find(|(total_id, _)| *total_id == id) {
+                    Some((_, total)) => *total = total.checked_add(value).ok_or(Error::InSufficientBalance)?,
+                    None => totals.push((id, value)),
+                }
+            }
+            for &(id, total) in totals.iter() {
+                if self.balance_of(from, id) < total {
+                    return Err(Error::InSufficientBalance);
+                }
+            }
+
+            for (&id, &value) in ids.iter().zip(values.iter()) {
+                let from_balance = self.balance_of(from, id) - value;
+                self._move(from, from_balance, to, id, value);
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator,
+                from,
+                to,
+                ids,
+                values
+            });
+            Ok(())
+        }
+
+        /// Returns the caller if it may move the tokens of `owner`.
+        fn ensure_approved(&self, owner: AccountId) -> Result<AccountId> {
+            let caller = Self::env().caller();
+            if caller != owner && !self.is_approved_for_all(owner, caller) {
+                return Err(Error::NotApproved);
+            }
+            Ok(caller)
+        }
+
+        fn _mint(&mut self, operator: AccountId, to: AccountId, id: TokenId, value: Balance) -> Result<()> {
+            let total_supply = self.total_supply(id).checked_add(value).ok_or(Error::Overflow)?;
+            // At most the new total supply of `id`.
+            let to_balance = self.balance_of(to, id) + value;
+            self.total_supply.insert(id, total_supply);
+            self.balances.insert((id, to), to_balance);
+
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: None,
+                to,
+                id,
+                value
+            });
+            Ok(())
+        }
+
+        /// Set the balance of `from` to `from_balance` and credit `value` to `to`.
+        fn _move(&mut self, from: AccountId, from_balance: Balance, to: AccountId, id: TokenId, value: Balance) {
+            self.balances.insert((id, from), from_balance);
+            // `from` and `to` together never hold more than the total supply of `id`.
+            let to_balance = self.balance_of(to, id) + value;
+            self.balances.insert((id, to), to_balance);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env;
+
+        use ink_lang as ink;
+        use test_utils::{default_accounts, last_event, set_caller};
+
+        /// Alice creates a fungible token 0 with 1000 units and a non-fungible token 1.
+        fn setup() -> Erc1155 {
+            let mut erc1155 = Erc1155::new();
+            assert_eq!(erc1155.create(1000, false), Ok(0));
+            assert_eq!(erc1155.create(1, true), Ok(1));
+            erc1155
+        }
+
+        #[ink::test]
+        fn create_assigns_new_ids() {
+            let erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert_eq!(erc1155.balance_of(accounts.alice, 0), 1000);
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 1);
+            assert_eq!(erc1155.total_supply(1), 1);
+            assert_eq!(erc1155.creator_of(1), Some(accounts.alice));
+            assert_eq!(erc1155.creator_of(2), None);
+            match last_event() {
+                Event::TransferSingle(TransferSingle { operator, from, to, id, value }) => {
+                    assert_eq!(operator, accounts.alice);
+                    assert_eq!(from, None);
+                    assert_eq!(to, accounts.alice);
+                    assert_eq!(id, 1);
+                    assert_eq!(value, 1);
+                }
+                _ => panic!("expected a TransferSingle event"),
+            }
+        }
+
+        #[ink::test]
+        fn only_the_creator_can_mint() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert_eq!(erc1155.mint(accounts.bob, 2, 10), Err(Error::UnknownToken));
+            assert_eq!(erc1155.mint(accounts.bob, 0, 10), Ok(()));
+            assert_eq!(erc1155.balance_of(accounts.bob, 0), 10);
+            assert_eq!(erc1155.total_supply(0), 1010);
+
+            set_caller(accounts.bob);
+            assert_eq!(erc1155.mint(accounts.bob, 0, 10), Err(Error::NotCreator));
+            assert_eq!(erc1155.total_supply(0), 1010);
+        }
+
+        #[ink::test]
+        fn non_fungible_tokens_cannot_be_minted() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert!(erc1155.is_fixed_supply(1));
+            assert!(!erc1155.is_fixed_supply(0));
+            assert_eq!(erc1155.mint(accounts.alice, 1, 1), Err(Error::FixedSupply));
+            assert_eq!(erc1155.mint(accounts.bob, 1, 1), Err(Error::FixedSupply));
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 1);
+            assert_eq!(erc1155.total_supply(1), 1);
+
+            set_caller(accounts.bob);
+            assert_eq!(erc1155.mint(accounts.bob, 1, 1), Err(Error::NotCreator));
+        }
+
+        #[ink::test]
+        fn supply_cannot_overflow() {
+            let mut erc1155 = Erc1155::new();
+            let accounts = default_accounts();
+
+            assert_eq!(erc1155.create(Balance::MAX, false), Ok(0));
+            assert_eq!(erc1155.mint(accounts.bob, 0, 1), Err(Error::Overflow));
+            assert_eq!(erc1155.balance_of(accounts.bob, 0), 0);
+            assert_eq!(erc1155.total_supply(0), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn balance_of_batch_works() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.bob, 0, 40, Vec::new()), Ok(()));
+
+            assert_eq!(
+                erc1155.balance_of_batch(
+                    vec![accounts.alice, accounts.bob, accounts.bob, accounts.charlie],
+                    vec![0, 0, 1, 0],
+                ),
+                Ok(vec![960, 40, 0, 0]),
+            );
+            assert_eq!(erc1155.balance_of_batch(vec![accounts.alice], vec![0, 1]), Err(Error::LengthMismatch));
+        }
+
+        #[ink::test]
+        fn safe_transfer_from_works() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.bob, 1, 2, Vec::new()), Err(Error::InSufficientBalance));
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.bob, 1, 1, vec![1, 2, 3]), Ok(()));
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 0);
+            assert_eq!(erc1155.balance_of(accounts.bob, 1), 1);
+            match last_event() {
+                Event::TransferSingle(TransferSingle { operator, from, to, id, value }) => {
+                    assert_eq!(operator, accounts.alice);
+                    assert_eq!(from, Some(accounts.alice));
+                    assert_eq!(to, accounts.bob);
+                    assert_eq!(id, 1);
+                    assert_eq!(value, 1);
+                }
+                _ => panic!("expected a TransferSingle event"),
+            }
+
+            // Sending to oneself keeps the balance.
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.alice, 0, 1000, Vec::new()), Ok(()));
+            assert_eq!(erc1155.balance_of(accounts.alice, 0), 1000);
+        }
+
+        #[ink::test]
+        fn operators_can_move_all_tokens() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            set_caller(accounts.bob);
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.bob, 0, 10, Vec::new()), Err(Error::NotApproved));
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![0], vec![10], Vec::new()),
+                Err(Error::NotApproved),
+            );
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc1155.set_approval_for_all(accounts.alice, true), Err(Error::SelfApproval));
+            assert_eq!(erc1155.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert!(erc1155.is_approved_for_all(accounts.alice, accounts.bob));
+            match last_event() {
+                Event::ApprovalForAll(ApprovalForAll { owner, operator, approved }) => {
+                    assert_eq!(owner, accounts.alice);
+                    assert_eq!(operator, accounts.bob);
+                    assert!(approved);
+                }
+                _ => panic!("expected an ApprovalForAll event"),
+            }
+
+            set_caller(accounts.bob);
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.charlie, 0, 10, Vec::new()), Ok(()));
+            assert_eq!(erc1155.balance_of(accounts.charlie, 0), 10);
+            match last_event() {
+                Event::TransferSingle(TransferSingle { operator, from, .. }) => {
+                    assert_eq!(operator, accounts.bob);
+                    assert_eq!(from, Some(accounts.alice));
+                }
+                _ => panic!("expected a TransferSingle event"),
+            }
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc1155.set_approval_for_all(accounts.bob, false), Ok(()));
+            assert!(!erc1155.is_approved_for_all(accounts.alice, accounts.bob));
+            set_caller(accounts.bob);
+            assert_eq!(erc1155.safe_transfer_from(accounts.alice, accounts.bob, 0, 10, Vec::new()), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn safe_batch_transfer_from_works() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![0, 1], vec![100, 1], Vec::new()),
+                Ok(()),
+            );
+            assert_eq!(
+                erc1155.balance_of_batch(vec![accounts.alice, accounts.alice, accounts.bob, accounts.bob], vec![0, 1, 0, 1]),
+                Ok(vec![900, 0, 100, 1]),
+            );
+            match last_event() {
+                Event::TransferBatch(TransferBatch { operator, from, to, ids, values }) => {
+                    assert_eq!(operator, accounts.alice);
+                    assert_eq!(from, accounts.alice);
+                    assert_eq!(to, accounts.bob);
+                    assert_eq!(ids, vec![0, 1]);
+                    assert_eq!(values, vec![100, 1]);
+                }
+                _ => panic!("expected a TransferBatch event"),
+            }
+        }
+
+        #[ink::test]
+        fn failed_batch_moves_nothing() {
+            let mut erc1155 = setup();
+            let accounts = default_accounts();
+
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![0, 1], vec![100], Vec::new()),
+                Err(Error::LengthMismatch),
+            );
+            // The second transfer of token 1 fails, so the first one must not happen either.
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![0, 1, 1], vec![100, 1, 1], Vec::new()),
+                Err(Error::InSufficientBalance),
+            );
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![0, 0], vec![Balance::MAX, 1], Vec::new()),
+                Err(Error::InSufficientBalance),
+            );
+            assert_eq!(
+                erc1155.balance_of_batch(vec![accounts.alice, accounts.alice, accounts.bob, accounts.bob], vec![0, 1, 0, 1]),
+                Ok(vec![1000, 1, 0, 0]),
+            );
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 use ink_lang as ink;
 
+pub use self::erc20::{Erc20, Error, PSP22Error};
+
 #[ink::contract]
 mod erc20 {
 
@@ -9,12 +11,20 @@ mod erc20 {
     /// Add new fields to the below struct in order
     /// to add new static storage fields to your contract.
     use ink_storage::collections::HashMap as StorageHashMap;
+    use ink_prelude::{string::String, vec::Vec};
 
     #[ink(storage)]
     pub struct Erc20 {
         total_supply: Balance,
         balances: StorageHashMap<AccountId, Balance>,
-        allowances: StorageHashMap<(AccountId, AccountId), Balance>
+        allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+        /// The only account allowed to mint, pause and hand the ownership over.
+        owner: AccountId,
+        /// No tokens can be moved, minted or burned while paused.
+        paused: bool,
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: u8,
     }
 
     #[ink(event)]
@@ -35,95 +45,324 @@ mod erc20 {
         value: Balance,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
         InSufficientBalance,
-        NotEnoughAllowance
+        NotEnoughAllowance,
+        /// The caller is not the owner.
+        NotOwner,
+        /// Tokens can't move while the contract is paused.
+        ContractPaused,
+        AlreadyPaused,
+        NotPaused,
+        /// A balance, allowance or the total supply would exceed `Balance::MAX`.
+        Overflow,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// The error of the PSP22 messages, encoded as PSP22 tooling expects. Errors of this
+    /// contract that PSP22 has no variant for are `Custom`, named after the `Error` variant.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
+    }
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InSufficientBalance => PSP22Error::InsufficientBalance,
+                Error::NotEnoughAllowance => PSP22Error::InsufficientAllowance,
+                Error::NotOwner => PSP22Error::Custom(String::from("NotOwner")),
+                Error::ContractPaused => PSP22Error::Custom(String::from("ContractPaused")),
+                Error::AlreadyPaused => PSP22Error::Custom(String::from("AlreadyPaused")),
+                Error::NotPaused => PSP22Error::Custom(String::from("NotPaused")),
+                Error::Overflow => PSP22Error::Custom(String::from("Overflow")),
+            }
+        }
+    }
+
+    pub type PSP22Result<T> = core::result::Result<T, PSP22Error>;
+
+    /// Messages carry the PSP22 selectors, so PSP22 tooling can call the contract
+    /// without its metadata. The PSP22 messages return a `PSP22Error`.
     impl Erc20 {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
+        /// Give the whole `total_supply` to the caller, without metadata.
         #[ink(constructor)]
         pub fn new(total_supply: Balance) -> Self {
+            Self::new_with_metadata(total_supply, None, None, 0)
+        }
+
+        /// Give the whole `total_supply` to the caller, with the name, symbol and
+        /// decimals wallets display.
+        #[ink(constructor)]
+        pub fn new_with_metadata(
+            total_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
             let caller = Self::env().caller();
             let mut balances = StorageHashMap::new();
             balances.insert(caller, total_supply);
             let instance = Self {
                 total_supply,
                 balances,
-                allowances: StorageHashMap::new()
+                allowances: StorageHashMap::new(),
+                owner: caller,
+                paused: false,
+                name,
+                symbol,
+                decimals,
             };
 
             instance
         }
 
-        #[ink(message)]
+        #[ink(message, selector = "0x162df8c2")]
         pub fn total_supply(&self) -> Balance {
             self.total_supply
         }
 
-        #[ink(message)]
+        #[ink(message, selector = "0x6568382f")]
         pub fn balance_of(&self, owner: AccountId) -> Balance {
             *self.balances.get(&owner).unwrap_or(&0)
         }
 
+        #[ink(message, selector = "0x3d261bd4")]
+        pub fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        #[ink(message, selector = "0x34205be5")]
+        pub fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        #[ink(message, selector = "0x7271b782")]
+        pub fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+
         #[ink(message)]
-        pub fn allowance_of(&self, owner: AccountId, spender: AccountId) -> Balance {
-            *self.allowances.get(&(owner, spender)).unwrap_or(&0)
+        pub fn owner(&self) -> AccountId {
+            self.owner
         }
 
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message, selector = "0x4d47d921")]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            *self.allowances.get(&(owner, spender)).unwrap_or(&0)
+        }
+
+        /// `data` is accepted for PSP22 compatibility and otherwise ignored.
+        #[ink(message, selector = "0xdb20f9f5")]
+        pub fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> PSP22Result<()> {
+            let _ = data;
             let who = Self::env().caller();
-            self._transfer_from_to(who, to, value)
+            self._transfer_from_to(who, to, value)?;
+            Ok(())
         }
 
         // allow spender to withdraw from caller's account
-        #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+        #[ink(message, selector = "0xb20f1bbd")]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> PSP22Result<()> {
             let owner = Self::env().caller();
-            self.allowances.insert((owner, spender), value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
+            self._approve(owner, spender, value);
+            Ok(())
+        }
+
+        /// Add `delta` to the allowance of `spender`. Unlike `approve`, this can't be
+        /// front-run into letting the spender use both the old and the new allowance.
+        #[ink(message, selector = "0x96d6b57a")]
+        pub fn increase_allowance(&mut self, spender: AccountId, delta: Balance) -> PSP22Result<()> {
+            let owner = Self::env().caller();
+            let allowance = self.allowance(owner, spender)
+                .checked_add(delta)
+                .ok_or(Error::Overflow)?;
+            self._approve(owner, spender, allowance);
+            Ok(())
+        }
+
+        /// Take `delta` off the allowance of `spender`.
+        #[ink(message, selector = "0xfecb57d5")]
+        pub fn decrease_allowance(&mut self, spender: AccountId, delta: Balance) -> PSP22Result<()> {
+            let owner = Self::env().caller();
+            let allowance = self.allowance(owner, spender)
+                .checked_sub(delta)
+                .ok_or(Error::NotEnoughAllowance)?;
+            self._approve(owner, spender, allowance);
+            Ok(())
+        }
+
+        /// Spend `value` of the allowance `from` gave to the caller. Emits an `Approval`
+        /// with the remaining allowance.
+        #[ink(message, selector = "0x54b3c76e")]
+        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> PSP22Result<()> {
+            let _ = data;
+            let who = Self::env().caller();
+            let from_allowance = self.allowance(from, who)
+                .checked_sub(value)
+                .ok_or(Error::NotEnoughAllowance)?;
+            self._transfer_from_to(from, to, value)?;
+            self._approve(from, who, from_allowance);
+            Ok(())
+        }
+
+        /// Create `value` new tokens for `to`. Owner only.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_paused()?;
+            let total_supply = self.total_supply.checked_add(value).ok_or(Error::Overflow)?;
+            // Bounded by the new total supply checked just above.
+            let to_balance = self.balance_of(to) + value;
+            self.balances.insert(to, to_balance);
+            self.total_supply = total_supply;
+
+            self.env().emit_event(Mint {
+                to,
                 value
             });
             Ok(())
         }
 
+        /// Destroy `value` of the caller's tokens.
         #[ink(message)]
-        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
-            let who = Self::env().caller();
-            let from_allowance = self.allowance_of(from, who);
-            if from_allowance < value {
-                return Err(Error::NotEnoughAllowance);
+        pub fn burn(&mut self, value: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
+            let from = Self::env().caller();
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InSufficientBalance);
             }
-            self._transfer_from_to(from, to, value)?;
-            self.allowances.insert((from, who), from_allowance - value);
+            self.balances.insert(from, from_balance - value);
+            self.total_supply -= value;
+
+            self.env().emit_event(Burn {
+                from,
+                value
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            let previous_owner = self.ensure_owner()?;
+            self.owner = new_owner;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner
+            });
             Ok(())
         }
 
-        // #[ink(message)]
-        // pub fn burn() {
+        /// Stop all transfers, mints and burns. Owner only.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            let by = self.ensure_owner()?;
+            if self.paused {
+                return Err(Error::AlreadyPaused);
+            }
+            self.paused = true;
+            self.env().emit_event(Paused { by });
+            Ok(())
+        }
 
-        // }
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            let by = self.ensure_owner()?;
+            if !self.paused {
+                return Err(Error::NotPaused);
+            }
+            self.paused = false;
+            self.env().emit_event(Unpaused { by });
+            Ok(())
+        }
 
-        // #[ink(message)]
-        // pub fn issue() {
+        /// Returns the caller if it is the owner.
+        fn ensure_owner(&self) -> Result<AccountId> {
+            let caller = Self::env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(caller)
+        }
 
-        // }
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+            Ok(())
+        }
+
+        fn _approve(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value
+            });
+        }
 
         fn _transfer_from_to(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
-            let from_balance = self.balance_of(from);
-            if from_balance < value {
-                return Err(Error::InSufficientBalance);
+            self.ensure_not_paused()?;
+            let from_balance = self.balance_of(from)
+                .checked_sub(value)
+                .ok_or(Error::InSufficientBalance)?;
+            if from != to {
+                // Compute the credit before debiting `from`, so an overflow leaves both balances as they were.
+                let to_balance = self.balance_of(to)
+                    .checked_add(value)
+                    .ok_or(Error::Overflow)?;
+                self.balances.insert(from, from_balance);
+                self.balances.insert(to, to_balance);
             }
-            self.balances.insert(from, from_balance - value);
-            let to_balance = self.balance_of(to);
-            self.balances.insert(to, to_balance + value);
 
             self.env().emit_event(Transfer {
                 from,
@@ -145,6 +384,7 @@ mod erc20 {
         use ink_env;
 
         use ink_lang as ink;
+        use test_utils::{default_accounts, last_event, set_caller};
 
         /// We test if the create contract works.
         #[ink::test]
@@ -166,7 +406,7 @@ mod erc20 {
             let mut erc20 = Erc20::new(1000);
             let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().expect("Can't get accounts");
             assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
-            assert_eq!(erc20.allowance_of(accounts.alice, accounts.bob), 100);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
         }
 
         #[ink::test]
@@ -175,7 +415,7 @@ mod erc20 {
             let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().expect("Can't get accounts");
 
             assert_eq!(erc20.balance_of(accounts.bob), 0);
-            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 100, Vec::new()), Ok(()));
             assert_eq!(erc20.balance_of(accounts.bob), 100);
         }
 
@@ -184,8 +424,8 @@ mod erc20 {
             let mut erc20 = Erc20::new(1000);
             let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().expect("Can't get accounts");
 
-            assert_eq!(erc20.allowance_of(accounts.alice, accounts.bob), 0);
-            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 100), Err(Error::NotEnoughAllowance));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 100, Vec::new()), Err(PSP22Error::InsufficientAllowance));
         }
 
         #[ink::test]
@@ -194,7 +434,7 @@ mod erc20 {
             let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().expect("Can't get accounts");
 
             assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
-            assert_eq!(erc20.allowance_of(accounts.alice, accounts.bob), 100);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
             assert_eq!(erc20.balance_of(accounts.bob), 0);
             // Get contract address.
             let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
@@ -211,8 +451,210 @@ mod erc20 {
                 1000000,
                 data,
             );
-            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 50, Vec::new()), Ok(()));
             assert_eq!(erc20.balance_of(accounts.bob), 50);
         }
+
+        #[ink::test]
+        fn creator_is_the_owner() {
+            let erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+            assert_eq!(erc20.owner(), accounts.alice);
+            assert!(!erc20.is_paused());
+        }
+
+        #[ink::test]
+        fn owner_can_mint() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.total_supply(), 1100);
+            match last_event() {
+                Event::Mint(Mint { to, value }) => {
+                    assert_eq!(to, accounts.bob);
+                    assert_eq!(value, 100);
+                }
+                _ => panic!("expected a Mint event"),
+            }
+
+            set_caller(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 100), Err(Error::NotOwner));
+            assert_eq!(erc20.total_supply(), 1100);
+        }
+
+        #[ink::test]
+        fn holders_can_burn() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.burn(1001), Err(Error::InSufficientBalance));
+            assert_eq!(erc20.burn(100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 900);
+            assert_eq!(erc20.total_supply(), 900);
+            match last_event() {
+                Event::Burn(Burn { from, value }) => {
+                    assert_eq!(from, accounts.alice);
+                    assert_eq!(value, 100);
+                }
+                _ => panic!("expected a Burn event"),
+            }
+        }
+
+        #[ink::test]
+        fn ownership_can_be_transferred() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(erc20.owner(), accounts.bob);
+            match last_event() {
+                Event::OwnershipTransferred(OwnershipTransferred { previous_owner, new_owner }) => {
+                    assert_eq!(previous_owner, accounts.alice);
+                    assert_eq!(new_owner, accounts.bob);
+                }
+                _ => panic!("expected an OwnershipTransferred event"),
+            }
+            assert_eq!(erc20.mint(accounts.alice, 100), Err(Error::NotOwner));
+            assert_eq!(erc20.transfer_ownership(accounts.alice), Err(Error::NotOwner));
+
+            set_caller(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+        }
+
+        #[ink::test]
+        fn pause_blocks_moving_tokens() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+
+            assert_eq!(erc20.pause(), Ok(()));
+            assert!(erc20.is_paused());
+            assert!(matches!(last_event(), Event::Paused(_)));
+            assert_eq!(erc20.pause(), Err(Error::AlreadyPaused));
+
+            assert_eq!(erc20.transfer(accounts.bob, 10, Vec::new()), Err(PSP22Error::Custom(String::from("ContractPaused"))));
+            assert_eq!(erc20.mint(accounts.bob, 10), Err(Error::ContractPaused));
+            assert_eq!(erc20.burn(10), Err(Error::ContractPaused));
+            set_caller(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 10, Vec::new()), Err(PSP22Error::Custom(String::from("ContractPaused"))));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
+            assert_eq!(erc20.unpause(), Err(Error::NotOwner));
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc20.unpause(), Ok(()));
+            assert!(matches!(last_event(), Event::Unpaused(_)));
+            assert_eq!(erc20.unpause(), Err(Error::NotPaused));
+            assert_eq!(erc20.transfer(accounts.bob, 10, Vec::new()), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn only_owner_can_pause() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            set_caller(accounts.bob);
+            assert_eq!(erc20.pause(), Err(Error::NotOwner));
+            assert!(!erc20.is_paused());
+        }
+
+        #[ink::test]
+        fn balances_near_max_do_not_overflow() {
+            let mut erc20 = Erc20::new(Balance::MAX);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::Overflow));
+            assert_eq!(erc20.total_supply(), Balance::MAX);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.transfer(accounts.bob, Balance::MAX, Vec::new()), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+            assert_eq!(erc20.balance_of(accounts.bob), Balance::MAX);
+            assert_eq!(erc20.transfer(accounts.bob, 1, Vec::new()), Err(PSP22Error::InsufficientBalance));
+
+            // The whole supply can go back and forth, and to the holder itself.
+            set_caller(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.bob, Balance::MAX, Vec::new()), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), Balance::MAX);
+            assert_eq!(erc20.transfer(accounts.alice, Balance::MAX, Vec::new()), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), Balance::MAX);
+
+            ink_env::test::pop_execution_context();
+            assert_eq!(erc20.burn(Balance::MAX), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, Balance::MAX), Ok(()));
+            assert_eq!(erc20.total_supply(), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn allowance_can_be_increased_and_decreased() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.increase_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 150);
+            match last_event() {
+                Event::Approval(Approval { owner, spender, value }) => {
+                    assert_eq!(owner, accounts.alice);
+                    assert_eq!(spender, accounts.bob);
+                    assert_eq!(value, 150);
+                }
+                _ => panic!("expected an Approval event"),
+            }
+
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 151), Err(PSP22Error::InsufficientAllowance));
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 150), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+
+            assert_eq!(erc20.approve(accounts.bob, Balance::MAX), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 1), Err(PSP22Error::Custom(String::from("Overflow"))));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn transfer_from_reports_the_remaining_allowance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+
+            set_caller(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 101, Vec::new()), Err(PSP22Error::InsufficientAllowance));
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 60, Vec::new()), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.charlie), 60);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 40);
+            match last_event() {
+                Event::Approval(Approval { owner, spender, value }) => {
+                    assert_eq!(owner, accounts.alice);
+                    assert_eq!(spender, accounts.bob);
+                    assert_eq!(value, 40);
+                }
+                _ => panic!("expected an Approval event"),
+            }
+        }
+
+        #[ink::test]
+        fn metadata_is_optional() {
+            let erc20 = Erc20::new(1000);
+            assert_eq!(erc20.token_name(), None);
+            assert_eq!(erc20.token_symbol(), None);
+            assert_eq!(erc20.token_decimals(), 0);
+
+            let erc20 = Erc20::new_with_metadata(1000, Some(String::from("Token")), Some(String::from("TKN")), 12);
+            assert_eq!(erc20.token_name(), Some(String::from("Token")));
+            assert_eq!(erc20.token_symbol(), Some(String::from("TKN")));
+            assert_eq!(erc20.token_decimals(), 12);
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn transfer_data_is_ignored() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts = default_accounts();
+
+            assert_eq!(erc20.transfer(accounts.bob, 100, vec![1, 2, 3]), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
     }
 }
@@ -0,0 +1,31 @@
+//! Helpers for the off-chain tests of the contracts of this lesson.
+
+use ink_env::{test, DefaultEnvironment, Environment};
+
+type AccountId = <DefaultEnvironment as Environment>::AccountId;
+
+/// The accounts funded by the off-chain environment.
+pub fn default_accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+    test::default_accounts::<DefaultEnvironment>().expect("Can't get accounts")
+}
+
+/// Push a new execution context, so the following messages are called by `caller`.
+pub fn set_caller(caller: AccountId) {
+    let callee = ink_env::account_id::<DefaultEnvironment>()
+        .unwrap_or([0x0; 32].into());
+    let data = test::CallData::new(ink_env::call::Selector::new([0x00; 4]));
+    test::push_execution_context::<DefaultEnvironment>(
+        caller,
+        callee,
+        1000000,
+        1000000,
+        data,
+    );
+}
+
+/// The last event emitted, decoded as the `Event` of the contract under test.
+pub fn last_event<E: scale::Decode>() -> E {
+    let event = test::recorded_events().last().expect("an event was emitted");
+    E::decode(&mut &event.data[..])
+        .expect("encountered invalid contract event data buffer")
+}
@@ -0,0 +1,420 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+/// Holds `Erc20` tokens for beneficiaries and releases them by block number: nothing
+/// before the cliff, then linearly from the start until the end of the schedule.
+#[ink::contract]
+mod vesting {
+
+    use erc20::Erc20;
+    use ink_env::call::FromAccountId;
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        traits::{PackedLayout, SpreadLayout},
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, PackedLayout, SpreadLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Schedule {
+        /// Everything the beneficiary gets once fully vested.
+        total: Balance,
+        released: Balance,
+        start: BlockNumber,
+        /// Nothing can be released before this block.
+        cliff: BlockNumber,
+        /// Blocks from `start` until everything is vested.
+        duration: BlockNumber,
+        revocable: bool,
+        revoked: bool,
+    }
+
+    impl Schedule {
+        /// Tokens vested at block `now`, released or not.
+        pub fn vested_at(&self, now: BlockNumber) -> Balance {
+            if now < self.cliff {
+                return 0;
+            }
+            let elapsed = now.saturating_sub(self.start);
+            if self.revoked || elapsed >= self.duration {
+                return self.total;
+            }
+            // `total * elapsed / duration`, split so it can't overflow.
+            let (elapsed, duration) = (elapsed as Balance, self.duration as Balance);
+            self.total / duration * elapsed + self.total % duration * elapsed / duration
+        }
+
+        /// Everything was released, which for a revoked schedule is what had vested.
+        /// The beneficiary can then be given a new schedule.
+        pub fn is_finished(&self) -> bool {
+            self.released == self.total
+        }
+    }
+
+    #[ink(storage)]
+    pub struct Vesting {
+        token: Erc20,
+        /// Funds the schedules and can revoke the revocable ones.
+        owner: AccountId,
+        schedules: StorageHashMap<AccountId, Schedule>,
+    }
+
+    #[ink(event)]
+    pub struct ScheduleAdded {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        total: Balance,
+        start: BlockNumber,
+        cliff: BlockNumber,
+        duration: BlockNumber,
+        revocable: bool,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        value: Balance,
+    }
+
+    /// `refunded` unvested tokens went back to the owner.
+    #[ink(event)]
+    pub struct Revoked {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        refunded: Balance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// The caller is not the owner.
+        NotOwner,
+        NoSchedule,
+        /// A beneficiary can only have one schedule until it is finished.
+        ScheduleExists,
+        /// The duration is zero, or the cliff is outside of the schedule.
+        InvalidSchedule,
+        NothingToRelease,
+        NotRevocable,
+        AlreadyRevoked,
+        /// The call into the token contract failed.
+        Token(erc20::PSP22Error),
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Vesting {
+        /// Vest tokens of the `Erc20` contract at `token`. The caller becomes the owner.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token: FromAccountId::from_account_id(token),
+                owner: Self::env().caller(),
+                schedules: StorageHashMap::new(),
+            }
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn schedule_of(&self, beneficiary: AccountId) -> Option<Schedule> {
+            self.schedules.get(&beneficiary).copied()
+        }
+
+        /// Tokens of `beneficiary` vested at the current block, released or not.
+        #[ink(message)]
+        pub fn vested_amount(&self, beneficiary: AccountId) -> Balance {
+            self.schedule_of(beneficiary)
+                .map(|schedule| schedule.vested_at(self.env().block_number()))
+                .unwrap_or(0)
+        }
+
+        /// Tokens `beneficiary` can release now.
+        #[ink(message)]
+        pub fn releasable_amount(&self, beneficiary: AccountId) -> Balance {
+            self.schedule_of(beneficiary)
+                .map(|schedule| schedule.vested_at(self.env().block_number()) - schedule.released)
+                .unwrap_or(0)
+        }
+
+        /// Vest `total` tokens for `beneficiary`, taken from the owner with `transfer_from`.
+        /// The owner must have approved this contract for at least `total` first. A finished
+        /// schedule of `beneficiary` is replaced.
+        #[ink(message)]
+        pub fn add_schedule(
+            &mut self,
+            beneficiary: AccountId,
+            total: Balance,
+            start: BlockNumber,
+            cliff: BlockNumber,
+            duration: BlockNumber,
+            revocable: bool,
+        ) -> Result<()> {
+            let owner = self.ensure_owner()?;
+            if self.schedule_of(beneficiary).map_or(false, |schedule| !schedule.is_finished()) {
+                return Err(Error::ScheduleExists);
+            }
+            if duration == 0 || cliff < start || cliff - start > duration {
+                return Err(Error::InvalidSchedule);
+            }
+
+            let this = self.env().account_id();
+            self.token.transfer_from(owner, this, total, Vec::new()).map_err(Error::Token)?;
+            self.schedules.insert(beneficiary, Schedule {
+                total,
+                released: 0,
+                start,
+                cliff,
+                duration,
+                revocable,
+                revoked: false,
+            });
+
+            self.env().emit_event(ScheduleAdded {
+                beneficiary,
+                total,
+                start,
+                cliff,
+                duration,
+                revocable
+            });
+            Ok(())
+        }
+
+        /// Send the caller the tokens vested for it and not released yet.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<Balance> {
+            let beneficiary = self.env().caller();
+            let mut schedule = self.schedule_of(beneficiary).ok_or(Error::NoSchedule)?;
+            let value = schedule.vested_at(self.env().block_number()) - schedule.released;
+            if value == 0 {
+                return Err(Error::NothingToRelease);
+            }
+
+            // A failed token transfer must leave `released` untouched, so update it afterwards.
+            self.token.transfer(beneficiary, value, Vec::new()).map_err(Error::Token)?;
+            schedule.released += value;
+            self.schedules.insert(beneficiary, schedule);
+
+            self.env().emit_event(Released {
+                beneficiary,
+                value
+            });
+            Ok(value)
+        }
+
+        /// Stop the schedule of `beneficiary` and refund the unvested tokens to the owner.
+        /// What is vested already stays releasable. Owner only.
+        #[ink(message)]
+        pub fn revoke(&mut self, beneficiary: AccountId) -> Result<()> {
+            let owner = self.ensure_owner()?;
+            let mut schedule = self.schedule_of(beneficiary).ok_or(Error::NoSchedule)?;
+            if !schedule.revocable {
+                return Err(Error::NotRevocable);
+            }
+            if schedule.revoked {
+                return Err(Error::AlreadyRevoked);
+            }
+
+            let vested = schedule.vested_at(self.env().block_number());
+            let refunded = schedule.total - vested;
+            if refunded > 0 {
+                self.token.transfer(owner, refunded, Vec::new()).map_err(Error::Token)?;
+            }
+            schedule.total = vested;
+            schedule.revoked = true;
+            self.schedules.insert(beneficiary, schedule);
+
+            self.env().emit_event(Revoked {
+                beneficiary,
+                refunded
+            });
+            Ok(())
+        }
+
+        /// Returns the caller if it is the owner.
+        fn ensure_owner(&self) -> Result<AccountId> {
+            let caller = Self::env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(caller)
+        }
+    }
+
+    /// The off-chain environment can't call other contracts, so these tests stop short
+    /// of the calls into `Erc20` and cover the schedule arithmetic and the checks made
+    /// before them. The calls themselves are covered against a node by `../e2e`.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env;
+
+        use ink_lang as ink;
+        use test_utils::{default_accounts, set_caller};
+
+        fn advance_blocks(n: u32) {
+            for _ in 0..n {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Can't advance block");
+            }
+        }
+
+        /// 1000 tokens from block 10 to block 110, with a cliff at block 30.
+        fn schedule(revocable: bool) -> Schedule {
+            Schedule {
+                total: 1000,
+                released: 0,
+                start: 10,
+                cliff: 30,
+                duration: 100,
+                revocable,
+                revoked: false,
+            }
+        }
+
+        fn new_vesting() -> Vesting {
+            let accounts = default_accounts();
+            Vesting::new(accounts.django)
+        }
+
+        #[ink::test]
+        fn nothing_vests_before_the_cliff() {
+            let schedule = schedule(false);
+            assert_eq!(schedule.vested_at(0), 0);
+            assert_eq!(schedule.vested_at(29), 0);
+            // What accrued since the start unlocks at the cliff.
+            assert_eq!(schedule.vested_at(30), 200);
+        }
+
+        #[ink::test]
+        fn vesting_is_linear_until_the_end() {
+            let schedule = schedule(false);
+            assert_eq!(schedule.vested_at(60), 500);
+            assert_eq!(schedule.vested_at(109), 990);
+            assert_eq!(schedule.vested_at(110), 1000);
+            assert_eq!(schedule.vested_at(u32::MAX), 1000);
+        }
+
+        #[ink::test]
+        fn large_totals_do_not_overflow() {
+            let schedule = Schedule {
+                total: Balance::MAX,
+                start: 0,
+                cliff: 0,
+                duration: 4,
+                ..schedule(false)
+            };
+            assert_eq!(schedule.vested_at(2), Balance::MAX / 2);
+            assert_eq!(schedule.vested_at(3), Balance::MAX / 4 * 3 + 2);
+            assert_eq!(schedule.vested_at(4), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn revoked_schedules_are_fully_vested() {
+            let schedule = Schedule {
+                total: 500,
+                revoked: true,
+                ..schedule(true)
+            };
+            assert_eq!(schedule.vested_at(29), 0);
+            assert_eq!(schedule.vested_at(30), 500);
+        }
+
+        #[ink::test]
+        fn only_the_owner_adds_schedules() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+            assert_eq!(vesting.owner(), accounts.alice);
+
+            set_caller(accounts.bob);
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 30, 100, false), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn add_schedule_checks_the_schedule() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 30, 0, false), Err(Error::InvalidSchedule));
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 9, 100, false), Err(Error::InvalidSchedule));
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 111, 100, false), Err(Error::InvalidSchedule));
+
+            vesting.schedules.insert(accounts.bob, schedule(false));
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 30, 100, false), Err(Error::ScheduleExists));
+        }
+
+        #[ink::test]
+        fn finished_schedules_can_be_replaced() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+
+            // Revoked, but what vested is still to be released.
+            vesting.schedules.insert(accounts.bob, Schedule { total: 500, revoked: true, ..schedule(true) });
+            assert!(!vesting.schedule_of(accounts.bob).unwrap().is_finished());
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 30, 100, false), Err(Error::ScheduleExists));
+
+            // Past the existence check, an invalid schedule is the next thing rejected.
+            vesting.schedules.insert(accounts.bob, Schedule { total: 500, released: 500, revoked: true, ..schedule(true) });
+            assert_eq!(vesting.add_schedule(accounts.bob, 1000, 10, 30, 0, false), Err(Error::InvalidSchedule));
+            vesting.schedules.insert(accounts.charlie, Schedule { released: 1000, ..schedule(false) });
+            assert_eq!(vesting.add_schedule(accounts.charlie, 1000, 10, 30, 0, false), Err(Error::InvalidSchedule));
+        }
+
+        #[ink::test]
+        fn release_needs_vested_tokens() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+
+            set_caller(accounts.bob);
+            assert_eq!(vesting.release(), Err(Error::NoSchedule));
+
+            vesting.schedules.insert(accounts.bob, schedule(false));
+            advance_blocks(29);
+            assert_eq!(vesting.vested_amount(accounts.bob), 0);
+            assert_eq!(vesting.release(), Err(Error::NothingToRelease));
+
+            advance_blocks(31);
+            assert_eq!(vesting.vested_amount(accounts.bob), 500);
+            assert_eq!(vesting.releasable_amount(accounts.bob), 500);
+            vesting.schedules.insert(accounts.bob, Schedule { released: 500, ..schedule(false) });
+            assert_eq!(vesting.releasable_amount(accounts.bob), 0);
+            assert_eq!(vesting.release(), Err(Error::NothingToRelease));
+        }
+
+        #[ink::test]
+        fn revoke_checks_the_schedule() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+
+            assert_eq!(vesting.revoke(accounts.bob), Err(Error::NoSchedule));
+
+            vesting.schedules.insert(accounts.bob, schedule(false));
+            assert_eq!(vesting.revoke(accounts.bob), Err(Error::NotRevocable));
+
+            vesting.schedules.insert(accounts.charlie, Schedule { revoked: true, ..schedule(true) });
+            assert_eq!(vesting.revoke(accounts.charlie), Err(Error::AlreadyRevoked));
+
+            vesting.schedules.insert(accounts.eve, schedule(true));
+            set_caller(accounts.bob);
+            assert_eq!(vesting.revoke(accounts.eve), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn revoke_keeps_the_schedule() {
+            let mut vesting = new_vesting();
+            let accounts = default_accounts();
+
+            // Nothing is vested and nothing to refund either, no call into the token is made.
+            vesting.schedules.insert(accounts.bob, Schedule { total: 0, ..schedule(true) });
+            assert_eq!(vesting.revoke(accounts.bob), Ok(()));
+            let schedule = vesting.schedule_of(accounts.bob).expect("the schedule is kept");
+            assert!(schedule.revoked);
+            assert_eq!(vesting.releasable_amount(accounts.bob), 0);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Runtime API definition for the erc20 pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// Metadata and state of a token.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TokenInfo<AccountId, TokenId, TokenBalance> {
+    pub id: TokenId,
+    pub issuer: AccountId,
+    pub name: Vec<u8>,
+    pub ticker: Vec<u8>,
+    pub decimals: u8,
+    pub total_supply: TokenBalance,
+    pub paused: bool,
+    /// The LP token of the swap pool of this token, if it has one.
+    pub pool: Option<TokenId>,
+}
+
+/// A page of token holders, with the cursor to pass to get the next page.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HoldersPage<AccountId, TokenBalance> {
+    pub holders: Vec<(AccountId, TokenBalance)>,
+    /// The last holder of the page, the next page starts after it. None once there
+    /// are no more holders.
+    pub next: Option<AccountId>,
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait Erc20Api<AccountId, TokenId, TokenBalance> where
+        AccountId: Codec,
+        TokenId: Codec,
+        TokenBalance: Codec,
+    {
+        /// Tokens of `token_id` held by `who`.
+        fn balance_of(token_id: TokenId, who: AccountId) -> TokenBalance;
+
+        /// Tokens of `token_id` that `spender` may still move out of `owner`.
+        fn allowance(token_id: TokenId, owner: AccountId, spender: AccountId) -> TokenBalance;
+
+        /// None if there is no such token.
+        fn token_info(token_id: TokenId) -> Option<TokenInfo<AccountId, TokenId, TokenBalance>>;
+
+        /// Accounts holding some `token_id`, starting at `cursor` or at the first one when `None`.
+        /// The order is the storage order, stable as long as the holders don't change.
+        fn holders(
+            token_id: TokenId,
+            cursor: Option<AccountId>,
+            limit: u32,
+        ) -> HoldersPage<AccountId, TokenBalance>;
+
+        /// Tokens received for `currency_in` of native currency, fee included.
+        /// None if the token has no pool or the pool is empty.
+        fn quote_currency_to_tokens(token_id: TokenId, currency_in: TokenBalance) -> Option<TokenBalance>;
+
+        /// Native currency received for `tokens_in`, fee included.
+        /// None if the token has no pool or the pool is empty.
+        fn quote_tokens_to_currency(token_id: TokenId, tokens_in: TokenBalance) -> Option<TokenBalance>;
+    }
+}
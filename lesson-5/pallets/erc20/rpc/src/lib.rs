@@ -0,0 +1,194 @@
+//! RPC interface for the erc20 pallet.
+//!
+//! Balances are sent and returned as decimal strings, they don't fit in a JS number.
+
+use std::{fmt::Display, str::FromStr, sync::Arc};
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use pallet_erc20_rpc_runtime_api::{Erc20Api as Erc20RuntimeApi, HoldersPage, TokenInfo};
+
+/// Erc20 RPC methods.
+#[rpc]
+pub trait Erc20Api<BlockHash, AccountId, TokenId> {
+    /// Tokens of `token_id` held by `who`.
+    #[rpc(name = "erc20_balanceOf")]
+    fn balance_of(&self, token_id: TokenId, who: AccountId, at: Option<BlockHash>) -> Result<String>;
+
+    /// Tokens of `token_id` that `spender` may still move out of `owner`.
+    #[rpc(name = "erc20_allowance")]
+    fn allowance(
+        &self,
+        token_id: TokenId,
+        owner: AccountId,
+        spender: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<String>;
+
+    /// Metadata and total supply of `token_id`, null if there is no such token.
+    #[rpc(name = "erc20_tokenInfo")]
+    fn token_info(&self, token_id: TokenId, at: Option<BlockHash>) -> Result<Option<TokenInfo<AccountId, TokenId, String>>>;
+
+    /// Holders of `token_id`, `limit` at a time. Pass the returned `next` as `cursor`
+    /// to get the holders after it.
+    #[rpc(name = "erc20_holders")]
+    fn holders(
+        &self,
+        token_id: TokenId,
+        cursor: Option<AccountId>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<HoldersPage<AccountId, String>>;
+
+    /// Tokens received for `currency_in` of native currency, null without liquidity.
+    #[rpc(name = "erc20_quoteCurrencyToTokens")]
+    fn quote_currency_to_tokens(&self, token_id: TokenId, currency_in: String, at: Option<BlockHash>) -> Result<Option<String>>;
+
+    /// Native currency received for `tokens_in`, null without liquidity.
+    #[rpc(name = "erc20_quoteTokensToCurrency")]
+    fn quote_tokens_to_currency(&self, token_id: TokenId, tokens_in: String, at: Option<BlockHash>) -> Result<Option<String>>;
+}
+
+/// A struct that implements the [`Erc20Api`].
+pub struct Erc20<C, B, TokenBalance> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<(B, TokenBalance)>,
+}
+
+impl<C, B, TokenBalance> Erc20<C, B, TokenBalance> {
+    /// Create new `Erc20` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Erc20 { client, _marker: Default::default() }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+    /// An amount is not a decimal number fitting the balance type.
+    InvalidAmount,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::InvalidAmount => 2,
+        }
+    }
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: "Unable to query erc20 tokens.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+fn parse_amount<TokenBalance: FromStr>(amount: &str) -> Result<TokenBalance> {
+    amount.parse().map_err(|_| RpcError {
+        code: ErrorCode::ServerError(Error::InvalidAmount.into()),
+        message: "Amounts must be decimal strings.".into(),
+        data: Some(amount.into()),
+    })
+}
+
+impl<C, Block, AccountId, TokenId, TokenBalance>
+    Erc20Api<<Block as BlockT>::Hash, AccountId, TokenId>
+    for Erc20<C, Block, TokenBalance>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: Erc20RuntimeApi<Block, AccountId, TokenId, TokenBalance>,
+    AccountId: Codec,
+    TokenId: Codec,
+    TokenBalance: Codec + Display + FromStr + Send + Sync + 'static,
+{
+    fn balance_of(&self, token_id: TokenId, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<String> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+
+        api.balance_of(&at, token_id, who).map(|balance| balance.to_string()).map_err(runtime_error)
+    }
+
+    fn allowance(
+        &self,
+        token_id: TokenId,
+        owner: AccountId,
+        spender: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<String> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+
+        api.allowance(&at, token_id, owner, spender).map(|value| value.to_string()).map_err(runtime_error)
+    }
+
+    fn token_info(&self, token_id: TokenId, at: Option<<Block as BlockT>::Hash>) -> Result<Option<TokenInfo<AccountId, TokenId, String>>> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+
+        let info = api.token_info(&at, token_id).map_err(runtime_error)?;
+        Ok(info.map(|info| TokenInfo {
+            id: info.id,
+            issuer: info.issuer,
+            name: info.name,
+            ticker: info.ticker,
+            decimals: info.decimals,
+            total_supply: info.total_supply.to_string(),
+            paused: info.paused,
+            pool: info.pool,
+        }))
+    }
+
+    fn holders(
+        &self,
+        token_id: TokenId,
+        cursor: Option<AccountId>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<HoldersPage<AccountId, String>> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+
+        let page = api.holders(&at, token_id, cursor, limit).map_err(runtime_error)?;
+        Ok(HoldersPage {
+            holders: page.holders.into_iter().map(|(who, balance)| (who, balance.to_string())).collect(),
+            next: page.next,
+        })
+    }
+
+    fn quote_currency_to_tokens(&self, token_id: TokenId, currency_in: String, at: Option<<Block as BlockT>::Hash>) -> Result<Option<String>> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+        let currency_in = parse_amount(&currency_in)?;
+
+        api.quote_currency_to_tokens(&at, token_id, currency_in)
+            .map(|tokens| tokens.map(|tokens| tokens.to_string()))
+            .map_err(runtime_error)
+    }
+
+    fn quote_tokens_to_currency(&self, token_id: TokenId, tokens_in: String, at: Option<<Block as BlockT>::Hash>) -> Result<Option<String>> {
+        let api = self.client.runtime_api();
+        let at = self.at(at);
+        let tokens_in = parse_amount(&tokens_in)?;
+
+        api.quote_tokens_to_currency(&at, token_id, tokens_in)
+            .map(|currency| currency.map(|currency| currency.to_string()))
+            .map_err(runtime_error)
+    }
+}
+
+impl<C, Block: BlockT, TokenBalance> Erc20<C, Block, TokenBalance> where C: HeaderBackend<Block> {
+    fn at(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+        BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash
+        ))
+    }
+}
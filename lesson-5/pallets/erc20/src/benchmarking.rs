@@ -0,0 +1,367 @@
+//! Erc20 pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+/// A token issued by `issuer`, with the longest name and ticker, holding the whole supply.
+fn create_token<T: Trait>(issuer: &T::AccountId) -> Result<T::TokenId, &'static str> {
+    let supply = T::TokenBalance::max_value() / 2u32.into();
+    Module::<T>::do_create_token(
+        issuer.clone(),
+        vec![b'n'; MAX_NAME_LEN],
+        vec![b't'; MAX_TICKER_LEN],
+        18,
+        supply,
+    ).map_err(|_| "token can be created")
+}
+
+/// An account holding `amount` of `token_id`, sent by its issuer.
+fn holder<T: Trait>(name: &'static str, issuer: &T::AccountId, token_id: T::TokenId, amount: u32) -> Result<T::AccountId, &'static str> {
+    let who: T::AccountId = account(name, 0, SEED);
+    Module::<T>::transfer(RawOrigin::Signed(issuer.clone()).into(), token_id, who.clone(), amount.into())?;
+    Ok(who)
+}
+
+/// Give `who` plenty of native currency to swap and provide liquidity with.
+fn fund<T: Trait>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, 1_000_000_000u32.into());
+}
+
+/// A token of `provider` with a pool holding 1_000_000 currency and 2_000_000 tokens
+/// of the provider.
+fn create_funded_pool<T: Trait>(provider: &T::AccountId) -> Result<T::TokenId, &'static str> {
+    let token_id = create_token::<T>(provider)?;
+    fund::<T>(provider);
+    let origin: T::Origin = RawOrigin::Signed(provider.clone()).into();
+    Module::<T>::create_pool(origin.clone(), token_id)?;
+    Module::<T>::add_liquidity(origin, token_id, 1_000_000u32.into(), 2_000_000u32.into(), Zero::zero())?;
+    Ok(token_id)
+}
+
+benchmarks! {
+    _ { }
+
+    create_token {
+        let n in 0 .. MAX_NAME_LEN as u32;
+        let t in 0 .. MAX_TICKER_LEN as u32;
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let supply: T::TokenBalance = 1_000u32.into();
+        let token_id = Module::<T>::next_token_id();
+    }: _(RawOrigin::Signed(caller.clone()), vec![b'n'; n as usize], vec![b't'; t as usize], 18, supply)
+    verify {
+        assert_eq!(Module::<T>::balance_of(token_id, &caller), supply);
+    }
+
+    // Worst case: the recipient holds none of the token yet.
+    transfer {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        let value: T::TokenBalance = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller), token_id, recipient.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::balance_of(token_id, &recipient), value);
+    }
+
+    approve {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let spender: T::AccountId = account("spender", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        let value: T::TokenBalance = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), token_id, spender.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::allowance(token_id, (caller, spender)), value);
+    }
+
+    transfer_from {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&owner)?;
+        let value: T::TokenBalance = 1_000u32.into();
+        Module::<T>::approve(RawOrigin::Signed(owner.clone()).into(), token_id, caller.clone(), value)?;
+    }: _(RawOrigin::Signed(caller.clone()), token_id, owner.clone(), recipient.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::balance_of(token_id, &recipient), value);
+        assert_eq!(Module::<T>::allowance(token_id, (owner, caller)), Zero::zero());
+    }
+
+    mint {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        let value: T::TokenBalance = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller), token_id, recipient.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::balance_of(token_id, &recipient), value);
+    }
+
+    burn {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        let supply = Module::<T>::total_supply(token_id);
+        let value: T::TokenBalance = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller), token_id, value)
+    verify {
+        assert_eq!(Module::<T>::total_supply(token_id), supply - value);
+    }
+
+    burn_from {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_token::<T>(&owner)?;
+        let supply = Module::<T>::total_supply(token_id);
+        let value: T::TokenBalance = 1_000u32.into();
+        Module::<T>::approve(RawOrigin::Signed(owner.clone()).into(), token_id, caller.clone(), value)?;
+    }: _(RawOrigin::Signed(caller.clone()), token_id, owner.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::total_supply(token_id), supply - value);
+        assert_eq!(Module::<T>::allowance(token_id, (owner, caller)), Zero::zero());
+    }
+
+    transfer_ownership {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let new_issuer: T::AccountId = account("new_issuer", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), token_id, new_issuer.clone())
+    verify {
+        assert_eq!(Module::<T>::token_details(token_id).map(|token| token.issuer), Some(new_issuer));
+    }
+
+    pause {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), token_id)
+    verify {
+        assert!(Module::<T>::is_paused(token_id));
+    }
+
+    unpause {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        Module::<T>::pause(RawOrigin::Signed(caller.clone()).into(), token_id)?;
+    }: _(RawOrigin::Signed(caller), token_id)
+    verify {
+        assert!(!Module::<T>::is_paused(token_id));
+    }
+
+    freeze {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let who: T::AccountId = account("who", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), token_id, who.clone())
+    verify {
+        assert!(Module::<T>::is_frozen(token_id, &who));
+    }
+
+    thaw {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let who: T::AccountId = account("who", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        Module::<T>::freeze(RawOrigin::Signed(caller.clone()).into(), token_id, who.clone())?;
+    }: _(RawOrigin::Signed(caller), token_id, who.clone())
+    verify {
+        assert!(!Module::<T>::is_frozen(token_id, &who));
+    }
+
+    // Worst case: the issuer, not Root, so the freeze of `from` is checked.
+    force_transfer {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+        let from = holder::<T>("from", &caller, token_id, 1_000)?;
+        Module::<T>::freeze(RawOrigin::Signed(caller.clone()).into(), token_id, from.clone())?;
+        let value: T::TokenBalance = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller), token_id, from, recipient.clone(), value)
+    verify {
+        assert_eq!(Module::<T>::balance_of(token_id, &recipient), value);
+    }
+
+    create_pool {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_token::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), token_id)
+    verify {
+        assert!(Module::<T>::pool(token_id).is_some());
+    }
+
+    // Worst case: the pool already has liquidity, so its price is kept.
+    add_liquidity {
+        let provider: T::AccountId = account("provider", 0, SEED);
+        let token_id = create_funded_pool::<T>(&provider)?;
+        let caller = holder::<T>("caller", &provider, token_id, 1_000_000)?;
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), token_id, 1_000u32.into(), 1_000_000u32.into(), Zero::zero())
+    verify {
+        let shares = Module::<T>::pool(token_id).map(|lp_token| Module::<T>::balance_of(lp_token, &caller));
+        assert_eq!(shares, Some(1_000u32.into()));
+    }
+
+    remove_liquidity {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_funded_pool::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), token_id, 1_000u32.into(), Zero::zero(), Zero::zero())
+    verify {
+        assert_eq!(Module::<T>::reserves(token_id), (999_000u32.into(), 1_998_000u32.into()));
+    }
+
+    swap_currency_for_tokens {
+        let provider: T::AccountId = account("provider", 0, SEED);
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let token_id = create_funded_pool::<T>(&provider)?;
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), token_id, 1_000u32.into(), Zero::zero())
+    verify {
+        assert!(!Module::<T>::balance_of(token_id, &caller).is_zero());
+    }
+
+    swap_tokens_for_currency {
+        let provider: T::AccountId = account("provider", 0, SEED);
+        let token_id = create_funded_pool::<T>(&provider)?;
+        let caller = holder::<T>("caller", &provider, token_id, 1_000)?;
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), token_id, 1_000u32.into(), Zero::zero())
+    verify {
+        assert!(Module::<T>::balance_of(token_id, &caller).is_zero());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn create_token() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create_token::<Test>());
+        });
+    }
+
+    #[test]
+    fn transfer() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer::<Test>());
+        });
+    }
+
+    #[test]
+    fn approve() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_approve::<Test>());
+        });
+    }
+
+    #[test]
+    fn transfer_from() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_from::<Test>());
+        });
+    }
+
+    #[test]
+    fn mint() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_mint::<Test>());
+        });
+    }
+
+    #[test]
+    fn burn() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_burn::<Test>());
+        });
+    }
+
+    #[test]
+    fn burn_from() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_burn_from::<Test>());
+        });
+    }
+
+    #[test]
+    fn transfer_ownership() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_ownership::<Test>());
+        });
+    }
+
+    #[test]
+    fn pause() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_pause::<Test>());
+        });
+    }
+
+    #[test]
+    fn unpause() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_unpause::<Test>());
+        });
+    }
+
+    #[test]
+    fn freeze() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_freeze::<Test>());
+        });
+    }
+
+    #[test]
+    fn thaw() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_thaw::<Test>());
+        });
+    }
+
+    #[test]
+    fn force_transfer() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_force_transfer::<Test>());
+        });
+    }
+
+    #[test]
+    fn create_pool() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create_pool::<Test>());
+        });
+    }
+
+    #[test]
+    fn add_liquidity() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_add_liquidity::<Test>());
+        });
+    }
+
+    #[test]
+    fn remove_liquidity() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_remove_liquidity::<Test>());
+        });
+    }
+
+    #[test]
+    fn swap_currency_for_tokens() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_swap_currency_for_tokens::<Test>());
+        });
+    }
+
+    #[test]
+    fn swap_tokens_for_currency() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_swap_tokens_for_currency::<Test>());
+        });
+    }
+}
@@ -2,40 +2,142 @@
 use sp_std::prelude::*;
 use codec::{Codec,Encode,Decode};
 
-use frame_support::{Parameter,decl_module, decl_storage, decl_event, decl_error, dispatch::DispatchResult,ensure};
-use frame_system::{self as system, ensure_signed};
+use frame_support::{Parameter,decl_module, decl_storage, decl_event, decl_error, dispatch::DispatchResult,ensure,StorageMap,StorageDoubleMap,IterableStorageDoubleMap,StorageValue,transactional,traits::{Currency,ExistenceRequirement,Get}};
+use frame_system::{self as system, ensure_signed, RawOrigin};
 
-use sp_runtime::traits::{CheckedAdd,CheckedSub,Member,AtLeast32BitUnsigned};
+use sp_runtime::{DispatchError,ModuleId,Permill,helpers_128bit::multiply_by_rational,traits::{AccountIdConversion,BadOrigin,CheckedAdd,CheckedSub,Member,AtLeast32BitUnsigned,Bounded,One,Zero,Saturating,SaturatedConversion}};
+
+pub use pallet_erc20_rpc_runtime_api::{TokenInfo, HoldersPage};
+
+#[cfg(test)]
+mod mock;
+
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod tests;
+
+/// Longest token name, in bytes.
+pub const MAX_NAME_LEN: usize = 64;
+/// Longest token ticker, in bytes.
+pub const MAX_TICKER_LEN: usize = 32;
+/// Most holders returned by one `holders` call.
+pub const MAX_HOLDERS_PAGE: u32 = 100;
 
 pub trait Trait: system::Trait{
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
     type TokenBalance: CheckedAdd + CheckedSub + Parameter + Member + Codec + Default + Copy + AtLeast32BitUnsigned;
+    type TokenId: Parameter + Member + Codec + Default + Copy + AtLeast32BitUnsigned + Bounded;
+
+    /// The native currency tokens are swapped against.
+    type Currency: Currency<Self::AccountId, Balance = Self::TokenBalance>;
+
+    /// Derives the account of each swap pool.
+    type ModuleId: Get<ModuleId>;
+
+    /// Part of every swap input kept by the pool for its liquidity providers.
+    type SwapFee: Get<Permill>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
+
 #[derive(Encode,Decode,Default,Clone,PartialEq,Debug)]
-pub struct Erc20Token<U> {
-    name: Vec<u8>,
-    ticker:Vec<u8>,
-    total_supply:U,
+pub struct Erc20Token<AccountId,U> {
+    /// The account allowed to mint, first the one that created the token.
+    pub issuer: AccountId,
+    pub name: Vec<u8>,
+    pub ticker: Vec<u8>,
+    pub decimals: u8,
+    /// Always the sum of all balances of the token.
+    pub total_supply: U,
 }
 
 decl_storage!{
     trait Store for Module <T: Trait> as Erc20 {
-        Tokens get(fn  token_details):Erc20Token<T::TokenBalance>;
-        BalanceOf get(fn balance_of): map hasher(blake2_128_concat) T::AccountId => T::TokenBalance;
-        Allowance get (fn allowance): map hasher(blake2_128_concat) (T::AccountId,T::AccountId) => T::TokenBalance;
+        /// The id the next created token gets.
+        NextTokenId get(fn next_token_id): T::TokenId;
+        Tokens get(fn  token_details): map hasher(blake2_128_concat) T::TokenId => Option<Erc20Token<T::AccountId,T::TokenBalance>>;
+        BalanceOf get(fn balance_of): double_map hasher(blake2_128_concat) T::TokenId, hasher(blake2_128_concat) T::AccountId => T::TokenBalance;
+        /// Amount a spender may transfer on behalf of an owner. (owner, spender)
+        Allowance get (fn allowance): double_map hasher(blake2_128_concat) T::TokenId, hasher(blake2_128_concat) (T::AccountId,T::AccountId) => T::TokenBalance;
+        /// Tokens whose transfers, mints and burns are stopped.
+        Paused get(fn is_paused): map hasher(blake2_128_concat) T::TokenId => bool;
+        /// Accounts that can neither send, receive nor burn a token.
+        Frozen get(fn is_frozen): double_map hasher(blake2_128_concat) T::TokenId, hasher(blake2_128_concat) T::AccountId => bool;
+        /// LP token of the swap pool of each token. The pool shares are balances of it.
+        Pools get(fn pool): map hasher(blake2_128_concat) T::TokenId => Option<T::TokenId>;
+        /// Native currency and tokens of the swap pool of each token. Funds sent to the
+        /// pool account by other means are not part of them and don't move the price.
+        Reserves get(fn reserves): map hasher(blake2_128_concat) T::TokenId => (T::TokenBalance,T::TokenBalance);
     }
 }
 
 decl_event!{
-    pub enum Event<T> where AccountId = <T as system::Trait>::AccountId,<T as Trait>::TokenBalance{
-        Transfer(AccountId,AccountId,TokenBalance),
-        Approval(AccountId,AccountId,TokenBalance),
+    pub enum Event<T> where AccountId = <T as system::Trait>::AccountId,<T as Trait>::TokenBalance,<T as Trait>::TokenId{
+        /// A token is created. (token_id, issuer, total_supply)
+        Created(TokenId,AccountId,TokenBalance),
+        Transfer(TokenId,AccountId,AccountId,TokenBalance),
+        /// The allowance of a spender is set. (token_id, owner, spender, allowance)
+        Approval(TokenId,AccountId,AccountId,TokenBalance),
+        /// Tokens are minted by the issuer. (token_id, to, amount)
+        Minted(TokenId,AccountId,TokenBalance),
+        /// Tokens are burned. (token_id, from, amount)
+        Burned(TokenId,AccountId,TokenBalance),
+        /// The issuer role is handed over. (token_id, old_issuer, new_issuer)
+        IssuerChanged(TokenId,AccountId,AccountId),
+        /// Transfers of a token are paused. (token_id, by) `by` is None for Root
+        Paused(TokenId,Option<AccountId>),
+        /// Transfers of a token are resumed. (token_id, by)
+        Unpaused(TokenId,Option<AccountId>),
+        /// An account is frozen. (token_id, who, by)
+        Frozen(TokenId,AccountId,Option<AccountId>),
+        /// An account is thawed. (token_id, who, by)
+        Thawed(TokenId,AccountId,Option<AccountId>),
+        /// Tokens are moved out of an account by force. (token_id, from, to, amount, by)
+        ForceTransferred(TokenId,AccountId,AccountId,TokenBalance,Option<AccountId>),
+        /// A swap pool is created. (token_id, lp_token, creator)
+        PoolCreated(TokenId,TokenId,AccountId),
+        /// Liquidity is added to a pool. (token_id, who, currency, tokens, shares)
+        LiquidityAdded(TokenId,AccountId,TokenBalance,TokenBalance,TokenBalance),
+        /// Liquidity is taken out of a pool. (token_id, who, currency, tokens, shares)
+        LiquidityRemoved(TokenId,AccountId,TokenBalance,TokenBalance,TokenBalance),
+        /// Native currency is swapped for tokens. (token_id, who, currency_in, tokens_out)
+        CurrencySwapped(TokenId,AccountId,TokenBalance,TokenBalance),
+        /// Tokens are swapped for native currency. (token_id, who, tokens_in, currency_out)
+        TokensSwapped(TokenId,AccountId,TokenBalance,TokenBalance),
     }
 }
 
 decl_error!{
     pub enum Error for Module<T:Trait>{
         StorageOverflow,
+        InvalidTokenId,
+        TokenIdOverflow,
+        /// Only the issuer of the token can do this.
+        RequireIssuer,
+        /// Minting would overflow the total supply.
+        SupplyOverflow,
+        InsufficientBalance,
+        InsufficientAllowance,
+        /// The token is paused.
+        TokenPaused,
+        /// The account is frozen for this token.
+        AccountFrozen,
+        /// Only frozen accounts can be force-transferred from.
+        RequireFrozen,
+        PoolExists,
+        NoPool,
+        /// The pool has no liquidity to trade against.
+        EmptyPool,
+        ZeroAmount,
+        /// The price moved past the bounds given by the caller.
+        SlippageExceeded,
+        /// Swap pool accounts can't be frozen or force-transferred from, their
+        /// tokens belong to the liquidity providers.
+        PoolAccount,
     }
 }
 
@@ -44,47 +146,258 @@ decl_module!{
     pub struct Module<T: Trait> for  enum Call where origin: T::Origin{
         type Error = Error<T>;
         fn deposit_event() = default;
-        #[weight = 0]
-        fn init(origin,name:Vec<u8>,ticker:Vec<u8>,total_supply:T::TokenBalance)->DispatchResult{
+
+        /// Create a new token and give its whole supply to the sender
+        #[weight = T::WeightInfo::create_token(name.len() as u32,ticker.len() as u32)]
+        fn create_token(origin,name:Vec<u8>,ticker:Vec<u8>,decimals:u8,total_supply:T::TokenBalance)->DispatchResult{
             let sender = ensure_signed(origin)?;
-            ensure!(name.len() <= 64, "token name cannot exceed 64 bytes");
-            ensure!(ticker.len() <= 32, "token ticker cannot exceed 32 bytes");
+            Self::do_create_token(sender,name,ticker,decimals,total_supply)?;
+            Ok(())
+        }
 
-            let token = Erc20Token{
-                name,
-                ticker,
-                total_supply,
-            };
-            <Tokens<T>>::set(token);
-            <BalanceOf<T>>::insert(sender,total_supply);
+        #[weight = T::WeightInfo::transfer()]
+        fn transfer(_origin,token_id: T::TokenId,to: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(_origin)?;
+            Self::_transfer(token_id,sender,to,value)
+        }
+        /// Spend the allowance `from` gave to the sender
+        #[weight = T::WeightInfo::transfer_from()]
+        pub fn transfer_from(_origin,token_id: T::TokenId,from: T::AccountId,to: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            let spender = ensure_signed(_origin)?;
+            let allowance = Self::allowance(token_id,(from.clone(),spender.clone()));
+            let updated_allowance = allowance.checked_sub(&value).ok_or(Error::<T>::InsufficientAllowance)?;
 
+            Self::_transfer(token_id,from.clone(),to,value)?;
+            Self::set_allowance(token_id,from,spender,updated_allowance);
             Ok(())
         }
+        /// Set the allowance of `spender` to `value`
+        #[weight = T::WeightInfo::approve()]
+        fn approve(_origin,token_id: T::TokenId,spender: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(_origin)?;
+            ensure!(<Tokens<T>>::contains_key(token_id),Error::<T>::InvalidTokenId);
 
-        #[weight=0]
-        fn transfer(_origin,to: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            Self::set_allowance(token_id,sender,spender,value);
+            Ok(())
+        }
+        /// Add `added` to the allowance of `spender`
+        #[weight = T::WeightInfo::approve()]
+        fn increase_allowance(_origin,token_id: T::TokenId,spender: T::AccountId,added: T::TokenBalance)->DispatchResult{
             let sender = ensure_signed(_origin)?;
-            Self::_transfer(sender,to,value)
-        }
-        #[weight=0]
-        pub fn transfer_from(_origin,from: T::AccountId,to: T::AccountId,value: T::TokenBalance)->DispatchResult{
-            let allowance =Self::allowance((from.clone(),to.clone()));
-            ensure!(allowance>=value,"Not enough allowance.");
-            let updated_allowance = allowance.checked_sub(&value).ok_or(Error::<T>::StorageOverflow)?;
-            <Allowance<T>>::insert((from.clone(),to.clone()),updated_allowance);
-            Self::deposit_event(RawEvent::Approval(from.clone(),to.clone(),value));
-            Self::_transfer(from,to,value)
-        }
-        #[weight = 0]
-        fn approve(_origin, spender: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            ensure!(<Tokens<T>>::contains_key(token_id),Error::<T>::InvalidTokenId);
+
+            let allowance = Self::allowance(token_id,(sender.clone(),spender.clone()));
+            let updated_allowance = allowance.checked_add(&added).ok_or(Error::<T>::StorageOverflow)?;
+            Self::set_allowance(token_id,sender,spender,updated_allowance);
+            Ok(())
+        }
+        /// Take `subtracted` off the allowance of `spender`
+        #[weight = T::WeightInfo::approve()]
+        fn decrease_allowance(_origin,token_id: T::TokenId,spender: T::AccountId,subtracted: T::TokenBalance)->DispatchResult{
             let sender = ensure_signed(_origin)?;
+            ensure!(<Tokens<T>>::contains_key(token_id),Error::<T>::InvalidTokenId);
+
+            let allowance = Self::allowance(token_id,(sender.clone(),spender.clone()));
+            let updated_allowance = allowance.checked_sub(&subtracted).ok_or(Error::<T>::InsufficientAllowance)?;
+            Self::set_allowance(token_id,sender,spender,updated_allowance);
+            Ok(())
+        }
+        /// Create `amount` new tokens for `to`. Issuer only
+        #[weight = T::WeightInfo::mint()]
+        fn mint(origin,token_id: T::TokenId,to: T::AccountId,amount: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            Self::ensure_issuer(token_id,&sender)?;
+            Self::do_mint(token_id,to,amount)
+        }
+        /// Destroy `amount` of the sender's tokens
+        #[weight = T::WeightInfo::burn()]
+        fn burn(origin,token_id: T::TokenId,amount: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            Self::do_burn(token_id,sender,amount)
+        }
+        /// Destroy `amount` of the tokens of `from`, spending the allowance it gave to the sender
+        #[weight = T::WeightInfo::burn_from()]
+        fn burn_from(origin,token_id: T::TokenId,from: T::AccountId,amount: T::TokenBalance)->DispatchResult{
+            let spender = ensure_signed(origin)?;
+            let allowance = Self::allowance(token_id,(from.clone(),spender.clone()));
+            let updated_allowance = allowance.checked_sub(&amount).ok_or(Error::<T>::InsufficientAllowance)?;
+
+            Self::do_burn(token_id,from.clone(),amount)?;
+            Self::set_allowance(token_id,from,spender,updated_allowance);
+            Ok(())
+        }
+        /// Hand the issuer role over to `new_issuer`. Issuer only
+        #[weight = T::WeightInfo::transfer_ownership()]
+        fn transfer_ownership(origin,token_id: T::TokenId,new_issuer: T::AccountId)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let mut token = Self::ensure_issuer(token_id,&sender)?;
 
-            let allowance = Self::allowance((sender.clone(),sender.clone()));
-            let updated_allowance = allowance + value;
-            <Allowance<T>>::insert((sender.clone(),spender.clone()),updated_allowance);
+            token.issuer = new_issuer.clone();
+            <Tokens<T>>::insert(token_id,token);
 
-            Self::deposit_event(RawEvent::Approval(sender.clone(),sender.clone(),value));
+            Self::deposit_event(RawEvent::IssuerChanged(token_id,sender,new_issuer));
+            Ok(())
+        }
+        /// Stop all transfers, mints and burns of a token. Issuer or Root
+        #[weight = T::WeightInfo::pause()]
+        fn pause(origin,token_id: T::TokenId)->DispatchResult{
+            let by = Self::ensure_issuer_or_root(origin,token_id)?;
+            Paused::<T>::insert(token_id,true);
+            Self::deposit_event(RawEvent::Paused(token_id,by));
+            Ok(())
+        }
+        /// Resume transfers, mints and burns of a token. Issuer or Root
+        #[weight = T::WeightInfo::unpause()]
+        fn unpause(origin,token_id: T::TokenId)->DispatchResult{
+            let by = Self::ensure_issuer_or_root(origin,token_id)?;
+            Paused::<T>::remove(token_id);
+            Self::deposit_event(RawEvent::Unpaused(token_id,by));
+            Ok(())
+        }
+        /// Stop `who` from sending, receiving or burning a token. Issuer or Root
+        #[weight = T::WeightInfo::freeze()]
+        fn freeze(origin,token_id: T::TokenId,who: T::AccountId)->DispatchResult{
+            let by = Self::ensure_issuer_or_root(origin,token_id)?;
+            ensure!(who != Self::pool_account(token_id),Error::<T>::PoolAccount);
+            Frozen::<T>::insert(token_id,&who,true);
+            Self::deposit_event(RawEvent::Frozen(token_id,who,by));
+            Ok(())
+        }
+        /// Let a frozen account use a token again. Issuer or Root
+        #[weight = T::WeightInfo::thaw()]
+        fn thaw(origin,token_id: T::TokenId,who: T::AccountId)->DispatchResult{
+            let by = Self::ensure_issuer_or_root(origin,token_id)?;
+            Frozen::<T>::remove(token_id,&who);
+            Self::deposit_event(RawEvent::Thawed(token_id,who,by));
+            Ok(())
+        }
+        /// Move tokens out of a frozen account, even while paused. Issuer or Root,
+        /// Root can move them out of any account
+        #[weight = T::WeightInfo::force_transfer()]
+        fn force_transfer(origin,token_id: T::TokenId,from: T::AccountId,to: T::AccountId,value: T::TokenBalance)->DispatchResult{
+            let by = Self::ensure_issuer_or_root(origin,token_id)?;
+            // `Reserves` would no longer match what the pool holds
+            ensure!(from != Self::pool_account(token_id),Error::<T>::PoolAccount);
+            if by.is_some() {
+                ensure!(Self::is_frozen(token_id,&from),Error::<T>::RequireFrozen);
+            }
+
+            Self::move_balance(token_id,&from,&to,value)?;
+            Self::deposit_event(RawEvent::ForceTransferred(token_id,from,to,value,by));
+            Ok(())
+        }
+        /// Open a swap pool between a token and the native currency
+        #[weight = T::WeightInfo::create_pool()]
+        fn create_pool(origin,token_id: T::TokenId)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let token = Self::token_details(token_id).ok_or(Error::<T>::InvalidTokenId)?;
+            ensure!(!<Pools<T>>::contains_key(token_id),Error::<T>::PoolExists);
+
+            let mut ticker = b"LP-".to_vec();
+            ticker.extend_from_slice(&token.ticker);
+            ticker.truncate(MAX_TICKER_LEN);
 
+            // Nobody can sign for the pool account, so only this pallet mints shares
+            let lp_token = Self::do_create_token(Self::pool_account(token_id),b"Swap pool shares".to_vec(),ticker,token.decimals,Zero::zero())?;
+            <Pools<T>>::insert(token_id,lp_token);
+
+            Self::deposit_event(RawEvent::PoolCreated(token_id,lp_token,sender));
+            Ok(())
+        }
+        /// Deposit `currency` and the matching amount of tokens, at most `max_tokens`, for
+        /// at least `min_shares` pool shares. The first deposit sets the price
+        #[weight = T::WeightInfo::add_liquidity()]
+        #[transactional]
+        fn add_liquidity(origin,token_id: T::TokenId,currency: T::TokenBalance,max_tokens: T::TokenBalance,min_shares: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let lp_token = Self::pool(token_id).ok_or(Error::<T>::NoPool)?;
+            ensure!(!currency.is_zero(),Error::<T>::ZeroAmount);
+
+            let (currency_reserve,token_reserve) = Self::reserves(token_id);
+            let lp_supply = Self::total_supply(lp_token);
+            let (tokens,shares) = if lp_supply.is_zero() {
+                (max_tokens,currency)
+            } else {
+                // Keep the pool price: round the tokens up and the shares down
+                let tokens = Self::mul_div(currency,token_reserve,currency_reserve)?
+                    .checked_add(&One::one()).ok_or(Error::<T>::StorageOverflow)?;
+                (tokens,Self::mul_div(currency,lp_supply,currency_reserve)?)
+            };
+            ensure!(!tokens.is_zero() && !shares.is_zero(),Error::<T>::ZeroAmount);
+            ensure!(tokens <= max_tokens && shares >= min_shares,Error::<T>::SlippageExceeded);
+
+            let pool = Self::pool_account(token_id);
+            T::Currency::transfer(&sender,&pool,currency,ExistenceRequirement::KeepAlive)?;
+            Self::_transfer(token_id,sender.clone(),pool,tokens)?;
+            Self::do_mint(lp_token,sender.clone(),shares)?;
+            Self::set_reserves(token_id,currency_reserve.saturating_add(currency),token_reserve.saturating_add(tokens));
+
+            Self::deposit_event(RawEvent::LiquidityAdded(token_id,sender,currency,tokens,shares));
+            Ok(())
+        }
+        /// Burn `shares` for the matching part of the pool, at least `min_currency`
+        /// and `min_tokens`
+        #[weight = T::WeightInfo::remove_liquidity()]
+        #[transactional]
+        fn remove_liquidity(origin,token_id: T::TokenId,shares: T::TokenBalance,min_currency: T::TokenBalance,min_tokens: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let lp_token = Self::pool(token_id).ok_or(Error::<T>::NoPool)?;
+            ensure!(!shares.is_zero(),Error::<T>::ZeroAmount);
+
+            let (currency_reserve,token_reserve) = Self::reserves(token_id);
+            let lp_supply = Self::total_supply(lp_token);
+            ensure!(shares <= lp_supply,Error::<T>::InsufficientBalance);
+            let currency = Self::mul_div(shares,currency_reserve,lp_supply)?;
+            let tokens = Self::mul_div(shares,token_reserve,lp_supply)?;
+            ensure!(currency >= min_currency && tokens >= min_tokens,Error::<T>::SlippageExceeded);
+
+            Self::do_burn(lp_token,sender.clone(),shares)?;
+            let pool = Self::pool_account(token_id);
+            T::Currency::transfer(&pool,&sender,currency,ExistenceRequirement::AllowDeath)?;
+            Self::_transfer(token_id,pool,sender.clone(),tokens)?;
+            Self::set_reserves(token_id,currency_reserve - currency,token_reserve - tokens);
+
+            Self::deposit_event(RawEvent::LiquidityRemoved(token_id,sender,currency,tokens,shares));
+            Ok(())
+        }
+        /// Swap `currency_in` of native currency for at least `min_tokens_out` tokens
+        #[weight = T::WeightInfo::swap_currency_for_tokens()]
+        #[transactional]
+        fn swap_currency_for_tokens(origin,token_id: T::TokenId,currency_in: T::TokenBalance,min_tokens_out: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            ensure!(<Pools<T>>::contains_key(token_id),Error::<T>::NoPool);
+
+            let (currency_reserve,token_reserve) = Self::reserves(token_id);
+            let tokens_out = Self::output_amount(currency_in,currency_reserve,token_reserve).ok_or(Error::<T>::EmptyPool)?;
+            ensure!(!tokens_out.is_zero(),Error::<T>::ZeroAmount);
+            ensure!(tokens_out >= min_tokens_out,Error::<T>::SlippageExceeded);
+
+            let pool = Self::pool_account(token_id);
+            T::Currency::transfer(&sender,&pool,currency_in,ExistenceRequirement::KeepAlive)?;
+            Self::_transfer(token_id,pool,sender.clone(),tokens_out)?;
+            Self::set_reserves(token_id,currency_reserve.saturating_add(currency_in),token_reserve - tokens_out);
+
+            Self::deposit_event(RawEvent::CurrencySwapped(token_id,sender,currency_in,tokens_out));
+            Ok(())
+        }
+        /// Swap `tokens_in` tokens for at least `min_currency_out` of native currency
+        #[weight = T::WeightInfo::swap_tokens_for_currency()]
+        #[transactional]
+        fn swap_tokens_for_currency(origin,token_id: T::TokenId,tokens_in: T::TokenBalance,min_currency_out: T::TokenBalance)->DispatchResult{
+            let sender = ensure_signed(origin)?;
+            ensure!(<Pools<T>>::contains_key(token_id),Error::<T>::NoPool);
+
+            let (currency_reserve,token_reserve) = Self::reserves(token_id);
+            let currency_out = Self::output_amount(tokens_in,token_reserve,currency_reserve).ok_or(Error::<T>::EmptyPool)?;
+            ensure!(!currency_out.is_zero(),Error::<T>::ZeroAmount);
+            ensure!(currency_out >= min_currency_out,Error::<T>::SlippageExceeded);
+
+            let pool = Self::pool_account(token_id);
+            Self::_transfer(token_id,sender.clone(),pool.clone(),tokens_in)?;
+            T::Currency::transfer(&pool,&sender,currency_out,ExistenceRequirement::AllowDeath)?;
+            Self::set_reserves(token_id,currency_reserve - currency_out,token_reserve.saturating_add(tokens_in));
+
+            Self::deposit_event(RawEvent::TokensSwapped(token_id,sender,tokens_in,currency_out));
             Ok(())
         }
 
@@ -94,21 +407,218 @@ decl_module!{
 
 
 impl<T: Trait> Module<T> {
+    /// Create a token issued by `issuer`, who receives the whole supply. Returns its id.
+    pub fn do_create_token(
+        issuer: T::AccountId,
+        name: Vec<u8>,
+        ticker: Vec<u8>,
+        decimals: u8,
+        total_supply: T::TokenBalance,
+    ) -> Result<T::TokenId, DispatchError> {
+        ensure!(name.len() <= MAX_NAME_LEN, "token name cannot exceed 64 bytes");
+        ensure!(ticker.len() <= MAX_TICKER_LEN, "token ticker cannot exceed 32 bytes");
+
+        let token_id = Self::next_token_id();
+        ensure!(token_id != T::TokenId::max_value(), Error::<T>::TokenIdOverflow);
+
+        let token = Erc20Token{
+            issuer: issuer.clone(),
+            name,
+            ticker,
+            decimals,
+            total_supply,
+        };
+        <Tokens<T>>::insert(token_id,token);
+        <BalanceOf<T>>::insert(token_id,&issuer,total_supply);
+        NextTokenId::<T>::put(token_id + One::one());
+
+        Self::deposit_event(RawEvent::Created(token_id,issuer,total_supply));
+        Ok(token_id)
+    }
+
+    fn ensure_issuer(token_id: T::TokenId, who: &T::AccountId) -> Result<Erc20Token<T::AccountId,T::TokenBalance>, DispatchError> {
+        let token = Self::token_details(token_id).ok_or(Error::<T>::InvalidTokenId)?;
+        ensure!(&token.issuer == who,Error::<T>::RequireIssuer);
+        Ok(token)
+    }
+
+    /// Metadata and state of `token_id`, for the runtime API.
+    pub fn token_info(token_id: T::TokenId) -> Option<TokenInfo<T::AccountId, T::TokenId, T::TokenBalance>> {
+        let token = Self::token_details(token_id)?;
+        Some(TokenInfo {
+            id: token_id,
+            issuer: token.issuer,
+            name: token.name,
+            ticker: token.ticker,
+            decimals: token.decimals,
+            total_supply: token.total_supply,
+            paused: Self::is_paused(token_id),
+            pool: Self::pool(token_id),
+        })
+    }
+
+    /// Holders of `token_id` with a non-zero balance, at most `limit` (capped by
+    /// `MAX_HOLDERS_PAGE`) after the `cursor` account.
+    pub fn holders(token_id: T::TokenId, cursor: Option<T::AccountId>, limit: u32) -> HoldersPage<T::AccountId, T::TokenBalance> {
+        let mut balances = <BalanceOf<T>>::iter_prefix(token_id);
+        // Emptied balances stay in storage, so the cursor is found even if it has
+        // nothing left
+        if let Some(cursor) = cursor {
+            balances.by_ref().find(|(who,_)| *who == cursor);
+        }
+
+        let mut holders = balances.filter(|(_,balance)| !balance.is_zero());
+        let page: Vec<_> = holders.by_ref().take(limit.min(MAX_HOLDERS_PAGE) as usize).collect();
+        let next = match holders.next() {
+            Some(_) => page.last().map(|(who,_)| who.clone()),
+            None => None,
+        };
+        HoldersPage {
+            holders: page,
+            next,
+        }
+    }
+
+    /// The account holding the reserves of the swap pool of `token_id`.
+    pub fn pool_account(token_id: T::TokenId) -> T::AccountId {
+        T::ModuleId::get().into_sub_account(token_id)
+    }
+
+    /// Record the reserves of the swap pool of `token_id`. The pool account is reaped
+    /// when its currency falls below the existential deposit, that dust is no reserve.
+    fn set_reserves(token_id: T::TokenId, currency: T::TokenBalance, tokens: T::TokenBalance) {
+        let currency = currency.min(T::Currency::free_balance(&Self::pool_account(token_id)));
+        <Reserves<T>>::insert(token_id,(currency,tokens));
+    }
+
+    /// Tokens received for `currency_in` of native currency. None without liquidity.
+    pub fn quote_currency_to_tokens(token_id: T::TokenId, currency_in: T::TokenBalance) -> Option<T::TokenBalance> {
+        Self::pool(token_id)?;
+        let (currency_reserve,token_reserve) = Self::reserves(token_id);
+        Self::output_amount(currency_in,currency_reserve,token_reserve)
+    }
+
+    /// Native currency received for `tokens_in`. None without liquidity.
+    pub fn quote_tokens_to_currency(token_id: T::TokenId, tokens_in: T::TokenBalance) -> Option<T::TokenBalance> {
+        Self::pool(token_id)?;
+        let (currency_reserve,token_reserve) = Self::reserves(token_id);
+        Self::output_amount(tokens_in,token_reserve,currency_reserve)
+    }
+
+    /// Constant product output for `input`, after the swap fee. None if a reserve is empty.
+    fn output_amount(input: T::TokenBalance, input_reserve: T::TokenBalance, output_reserve: T::TokenBalance) -> Option<T::TokenBalance> {
+        if input_reserve.is_zero() || output_reserve.is_zero() {
+            return None;
+        }
+        let input = input - T::SwapFee::get() * input;
+        let output = multiply_by_rational(
+            input.saturated_into(),
+            output_reserve.saturated_into(),
+            input_reserve.saturating_add(input).saturated_into(),
+        ).ok()?;
+        Some(output.saturated_into())
+    }
+
+    /// `a * b / c` rounded down, without overflowing on the way.
+    fn mul_div(a: T::TokenBalance, b: T::TokenBalance, c: T::TokenBalance) -> Result<T::TokenBalance, DispatchError> {
+        ensure!(!c.is_zero(),Error::<T>::EmptyPool);
+        let result = multiply_by_rational(a.saturated_into(),b.saturated_into(),c.saturated_into())
+            .map_err(|_| Error::<T>::StorageOverflow)?;
+        Ok(result.saturated_into())
+    }
+
+    fn total_supply(token_id: T::TokenId) -> T::TokenBalance {
+        Self::token_details(token_id).map(|token| token.total_supply).unwrap_or_default()
+    }
+
+    fn do_mint(token_id: T::TokenId, to: T::AccountId, amount: T::TokenBalance) -> DispatchResult {
+        let mut token = Self::token_details(token_id).ok_or(Error::<T>::InvalidTokenId)?;
+        Self::ensure_can_move(token_id,&[&to])?;
+        token.total_supply = token.total_supply.checked_add(&amount).ok_or(Error::<T>::SupplyOverflow)?;
+        // No more than `token.total_supply`, which passed `checked_add`
+        let balance = Self::balance_of(token_id,&to) + amount;
+
+        <Tokens<T>>::insert(token_id,token);
+        <BalanceOf<T>>::insert(token_id,&to,balance);
+
+        Self::deposit_event(RawEvent::Minted(token_id,to,amount));
+        Ok(())
+    }
+
+    /// Check `origin` is Root or the issuer of the token. Returns the issuer, None for Root.
+    fn ensure_issuer_or_root(origin: T::Origin, token_id: T::TokenId) -> Result<Option<T::AccountId>, DispatchError> {
+        let origin: Result<RawOrigin<T::AccountId>, T::Origin> = origin.into();
+        match origin {
+            Ok(RawOrigin::Root) => {
+                ensure!(<Tokens<T>>::contains_key(token_id),Error::<T>::InvalidTokenId);
+                Ok(None)
+            },
+            Ok(RawOrigin::Signed(who)) => {
+                Self::ensure_issuer(token_id,&who)?;
+                Ok(Some(who))
+            },
+            _ => Err(BadOrigin.into()),
+        }
+    }
+
+    /// Check neither the token nor any of `accounts` is stopped.
+    fn ensure_can_move(token_id: T::TokenId, accounts: &[&T::AccountId]) -> DispatchResult {
+        ensure!(!Self::is_paused(token_id),Error::<T>::TokenPaused);
+        for who in accounts {
+            ensure!(!Self::is_frozen(token_id,*who),Error::<T>::AccountFrozen);
+        }
+        Ok(())
+    }
+
+    fn do_burn(token_id: T::TokenId, from: T::AccountId, amount: T::TokenBalance) -> DispatchResult {
+        let mut token = Self::token_details(token_id).ok_or(Error::<T>::InvalidTokenId)?;
+        Self::ensure_can_move(token_id,&[&from])?;
+        let balance = Self::balance_of(token_id,&from).checked_sub(&amount).ok_or(Error::<T>::InsufficientBalance)?;
+        // `amount` came out of a balance, which is counted in the total supply
+        token.total_supply = token.total_supply - amount;
+
+        <Tokens<T>>::insert(token_id,token);
+        <BalanceOf<T>>::insert(token_id,&from,balance);
+
+        Self::deposit_event(RawEvent::Burned(token_id,from,amount));
+        Ok(())
+    }
+
+    fn set_allowance(token_id: T::TokenId, owner: T::AccountId, spender: T::AccountId, value: T::TokenBalance) {
+        <Allowance<T>>::insert(token_id,(owner.clone(),spender.clone()),value);
+        Self::deposit_event(RawEvent::Approval(token_id,owner,spender,value));
+    }
+
     fn _transfer (
+        token_id: T::TokenId,
         from: T::AccountId,
         to: T::AccountId,
         value : T::TokenBalance,
     ) -> DispatchResult {
-        let sender_balance = Self::balance_of(from.clone());
-        ensure!(sender_balance >= value , "Not enough balance.");
+        ensure!(<Tokens<T>>::contains_key(token_id),Error::<T>::InvalidTokenId);
+        Self::ensure_can_move(token_id,&[&from,&to])?;
+
+        Self::move_balance(token_id,&from,&to,value)?;
+
+        Self::deposit_event(RawEvent::Transfer(token_id,from,to,value));
+        Ok(())
+    }
+
+    /// Move `value` from `from` to `to`, without any pause or freeze check.
+    fn move_balance(token_id: T::TokenId, from: &T::AccountId, to: &T::AccountId, value: T::TokenBalance) -> DispatchResult {
+        let sender_balance = Self::balance_of(token_id,from);
+        ensure!(sender_balance >= value , Error::<T>::InsufficientBalance);
+
+        // Both inserts below would hit the same entry
+        if from == to {
+            return Ok(());
+        }
 
         let updated_from_balance = sender_balance.checked_sub(&value).ok_or(Error::<T>::StorageOverflow)?;
-        let receiver_balance = Self::balance_of(to.clone());
+        let receiver_balance = Self::balance_of(token_id,to);
         let updated_to_balance = receiver_balance.checked_add(&value).ok_or(Error::<T>::StorageOverflow)?;
-        <BalanceOf<T>>::insert(from.clone(),updated_from_balance);
-        <BalanceOf<T>>::insert(to.clone(),updated_to_balance);
-
-        Self::deposit_event(RawEvent::Transfer(from,to,value));
+        <BalanceOf<T>>::insert(token_id,from,updated_from_balance);
+        <BalanceOf<T>>::insert(token_id,to,updated_to_balance);
         Ok(())
     }
-}
\ No newline at end of file
+}
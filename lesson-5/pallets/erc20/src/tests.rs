@@ -0,0 +1,674 @@
+use crate::{Erc20Token, Error, RawEvent, Reserves, TokenInfo, mock::*};
+use frame_support::{assert_ok, assert_noop, StorageMap};
+use std::collections::BTreeMap;
+
+fn last_event() -> TestEvent {
+	System::events().pop().expect("event expected").event
+}
+
+fn create_token(issuer: u64, ticker: &[u8], total_supply: u64) -> u32 {
+	Erc20::do_create_token(issuer, b"Token".to_vec(), ticker.to_vec(), 18, total_supply)
+		.expect("token can be created")
+}
+
+#[test]
+fn create_token_assigns_new_ids() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc20::create_token(Origin::signed(1), b"Alpha".to_vec(), b"ALP".to_vec(), 12, 1_000));
+		assert_eq!(create_token(2, b"BET", 500), 1);
+
+		assert_eq!(Erc20::next_token_id(), 2);
+		assert_eq!(Erc20::token_details(0), Some(Erc20Token {
+			issuer: 1,
+			name: b"Alpha".to_vec(),
+			ticker: b"ALP".to_vec(),
+			decimals: 12,
+			total_supply: 1_000,
+		}));
+		assert_eq!(Erc20::token_details(1).map(|t| t.issuer), Some(2));
+
+		// Creating a token leaves the others untouched.
+		assert_eq!(Erc20::balance_of(0, 1), 1_000);
+		assert_eq!(Erc20::balance_of(1, 2), 500);
+		assert_eq!(Erc20::balance_of(1, 1), 0);
+	});
+}
+
+#[test]
+fn create_token_caps_metadata() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Erc20::create_token(Origin::signed(1), vec![b'n'; 65], b"TKN".to_vec(), 18, 1),
+			"token name cannot exceed 64 bytes"
+		);
+		assert_noop!(
+			Erc20::create_token(Origin::signed(1), b"Token".to_vec(), vec![b't'; 33], 18, 1),
+			"token ticker cannot exceed 32 bytes"
+		);
+		assert_ok!(Erc20::create_token(Origin::signed(1), vec![b'n'; 64], vec![b't'; 32], 18, 1));
+	});
+}
+
+#[test]
+fn transfer_moves_only_the_given_token() {
+	new_test_ext().execute_with(|| {
+		let alpha = create_token(1, b"ALP", 100);
+		let beta = create_token(1, b"BET", 100);
+
+		assert_ok!(Erc20::transfer(Origin::signed(1), alpha, 2, 40));
+
+		assert_eq!(Erc20::balance_of(alpha, 1), 60);
+		assert_eq!(Erc20::balance_of(alpha, 2), 40);
+		assert_eq!(Erc20::balance_of(beta, 1), 100);
+		assert_eq!(Erc20::balance_of(beta, 2), 0);
+
+		assert_noop!(Erc20::transfer(Origin::signed(2), beta, 1, 1), Error::<Test>::InsufficientBalance);
+	});
+}
+
+#[test]
+fn unknown_token_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Erc20::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::InvalidTokenId);
+		assert_noop!(Erc20::approve(Origin::signed(1), 0, 2, 10), Error::<Test>::InvalidTokenId);
+	});
+}
+
+#[test]
+fn transfer_to_self_keeps_the_balance() {
+	new_test_ext().execute_with(|| {
+		let alpha = create_token(1, b"ALP", 100);
+
+		assert_ok!(Erc20::transfer(Origin::signed(1), alpha, 1, 40));
+
+		assert_eq!(Erc20::balance_of(alpha, 1), 100);
+		assert_noop!(Erc20::transfer(Origin::signed(1), alpha, 1, 101), Error::<Test>::InsufficientBalance);
+	});
+}
+
+#[test]
+fn token_info_reports_the_token_state() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Erc20::token_info(0), None);
+
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::create_pool(Origin::signed(1), token));
+		assert_ok!(Erc20::pause(Origin::signed(1), token));
+		assert_eq!(Erc20::token_info(token), Some(TokenInfo {
+			id: token,
+			issuer: 1,
+			name: b"Token".to_vec(),
+			ticker: b"TKN".to_vec(),
+			decimals: 18,
+			total_supply: 100,
+			paused: true,
+			pool: Erc20::pool(token),
+		}));
+	});
+}
+
+#[test]
+fn holders_are_paginated() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		let other = create_token(2, b"OTH", 100);
+		for &who in &[2, 3, 4] {
+			assert_ok!(Erc20::transfer(Origin::signed(1), token, who, 10));
+		}
+		// Emptied balances are not listed
+		assert_ok!(Erc20::transfer(Origin::signed(4), token, 1, 10));
+
+		let first = Erc20::holders(token, None, 2);
+		assert_eq!(first.holders.len(), 2);
+		let second = Erc20::holders(token, first.next, 2);
+		assert_eq!(second.next, None);
+
+		let mut holders: Vec<_> = first.holders.into_iter().chain(second.holders).collect();
+		holders.sort();
+		assert_eq!(holders, vec![(1, 80), (2, 10), (3, 10)]);
+
+		assert_eq!(Erc20::holders(other, None, 10).holders, vec![(2, 100)]);
+		assert_eq!(Erc20::holders(token, None, 0).holders, vec![]);
+	});
+}
+
+#[test]
+fn holders_page_after_an_emptied_cursor() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		for &who in &[2, 3, 4] {
+			assert_ok!(Erc20::transfer(Origin::signed(1), token, who, 10));
+		}
+
+		let first = Erc20::holders(token, None, 1);
+		let cursor = first.next.clone().unwrap();
+		assert_eq!(first.holders[0].0, cursor);
+
+		// The cursor account burns its whole balance before the next call
+		assert_ok!(Erc20::burn(Origin::signed(cursor), token, Erc20::balance_of(token, cursor)));
+
+		let mut holders = first.holders;
+		let mut next = Some(cursor);
+		while next.is_some() {
+			let page = Erc20::holders(token, next, 1);
+			holders.extend(page.holders);
+			next = page.next;
+		}
+		holders.sort();
+		assert_eq!(holders, vec![(1, 70), (2, 10), (3, 10), (4, 10)]);
+	});
+}
+
+#[test]
+fn approve_sets_the_allowance() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 30));
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 10));
+
+		assert_eq!(Erc20::allowance(token, (1, 2)), 10);
+		assert_eq!(Erc20::allowance(token, (1, 1)), 0);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Approval(token, 1, 2, 10)));
+	});
+}
+
+#[test]
+fn increase_and_decrease_allowance_are_checked() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_ok!(Erc20::increase_allowance(Origin::signed(1), token, 2, 30));
+		assert_ok!(Erc20::increase_allowance(Origin::signed(1), token, 2, 5));
+		assert_eq!(Erc20::allowance(token, (1, 2)), 35);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Approval(token, 1, 2, 35)));
+
+		assert_noop!(
+			Erc20::increase_allowance(Origin::signed(1), token, 2, u64::max_value()),
+			Error::<Test>::StorageOverflow
+		);
+		assert_noop!(Erc20::decrease_allowance(Origin::signed(1), token, 2, 36), Error::<Test>::InsufficientAllowance);
+
+		assert_ok!(Erc20::decrease_allowance(Origin::signed(1), token, 2, 35));
+		assert_eq!(Erc20::allowance(token, (1, 2)), 0);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Approval(token, 1, 2, 0)));
+	});
+}
+
+#[test]
+fn transfer_from_spends_the_caller_allowance() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 30));
+
+		// The recipient is not the spender.
+		assert_noop!(Erc20::transfer_from(Origin::signed(3), token, 1, 3, 10), Error::<Test>::InsufficientAllowance);
+		assert_noop!(Erc20::transfer_from(Origin::signed(2), token, 1, 3, 31), Error::<Test>::InsufficientAllowance);
+
+		assert_ok!(Erc20::transfer_from(Origin::signed(2), token, 1, 3, 10));
+		assert_eq!(Erc20::balance_of(token, 1), 90);
+		assert_eq!(Erc20::balance_of(token, 3), 10);
+		assert_eq!(Erc20::allowance(token, (1, 2)), 20);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Approval(token, 1, 2, 20)));
+	});
+}
+
+#[test]
+fn transfer_from_keeps_the_allowance_when_balance_is_short() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 10);
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 30));
+
+		assert_noop!(Erc20::transfer_from(Origin::signed(2), token, 1, 2, 20), Error::<Test>::InsufficientBalance);
+		assert_eq!(Erc20::allowance(token, (1, 2)), 30);
+	});
+}
+
+#[test]
+fn issuer_can_mint() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_noop!(Erc20::mint(Origin::signed(2), token, 2, 10), Error::<Test>::RequireIssuer);
+		assert_noop!(Erc20::mint(Origin::signed(1), 1, 2, 10), Error::<Test>::InvalidTokenId);
+		assert_noop!(Erc20::mint(Origin::signed(1), token, 2, u64::max_value()), Error::<Test>::SupplyOverflow);
+
+		assert_ok!(Erc20::mint(Origin::signed(1), token, 2, 10));
+		assert_eq!(Erc20::balance_of(token, 2), 10);
+		assert_eq!(Erc20::token_details(token).unwrap().total_supply, 110);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Minted(token, 2, 10)));
+	});
+}
+
+#[test]
+fn holders_can_burn() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 2, 30));
+
+		assert_noop!(Erc20::burn(Origin::signed(2), token, 31), Error::<Test>::InsufficientBalance);
+
+		assert_ok!(Erc20::burn(Origin::signed(2), token, 20));
+		assert_eq!(Erc20::balance_of(token, 2), 10);
+		assert_eq!(Erc20::token_details(token).unwrap().total_supply, 80);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Burned(token, 2, 20)));
+	});
+}
+
+#[test]
+fn burn_from_spends_the_caller_allowance() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 30));
+
+		assert_noop!(Erc20::burn_from(Origin::signed(3), token, 1, 10), Error::<Test>::InsufficientAllowance);
+		assert_noop!(Erc20::burn_from(Origin::signed(2), token, 1, 31), Error::<Test>::InsufficientAllowance);
+
+		assert_ok!(Erc20::burn_from(Origin::signed(2), token, 1, 10));
+		assert_eq!(Erc20::balance_of(token, 1), 90);
+		assert_eq!(Erc20::allowance(token, (1, 2)), 20);
+		assert_eq!(Erc20::token_details(token).unwrap().total_supply, 90);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Approval(token, 1, 2, 20)));
+	});
+}
+
+#[test]
+fn issuer_role_can_be_handed_over() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_noop!(Erc20::transfer_ownership(Origin::signed(2), token, 2), Error::<Test>::RequireIssuer);
+
+		assert_ok!(Erc20::transfer_ownership(Origin::signed(1), token, 2));
+		assert_eq!(Erc20::token_details(token).unwrap().issuer, 2);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::IssuerChanged(token, 1, 2)));
+
+		assert_noop!(Erc20::mint(Origin::signed(1), token, 1, 10), Error::<Test>::RequireIssuer);
+		assert_ok!(Erc20::mint(Origin::signed(2), token, 1, 10));
+	});
+}
+
+#[test]
+fn paused_token_cannot_move() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::approve(Origin::signed(1), token, 2, 50));
+
+		assert_noop!(Erc20::pause(Origin::signed(2), token), Error::<Test>::RequireIssuer);
+		assert_ok!(Erc20::pause(Origin::signed(1), token));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Paused(token, Some(1))));
+
+		assert_noop!(Erc20::transfer(Origin::signed(1), token, 2, 10), Error::<Test>::TokenPaused);
+		assert_noop!(Erc20::transfer_from(Origin::signed(2), token, 1, 2, 10), Error::<Test>::TokenPaused);
+		assert_noop!(Erc20::burn(Origin::signed(1), token, 10), Error::<Test>::TokenPaused);
+
+		assert_ok!(Erc20::unpause(Origin::signed(1), token));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Unpaused(token, Some(1))));
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 2, 10));
+	});
+}
+
+#[test]
+fn mint_respects_pause_and_freeze() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_ok!(Erc20::freeze(Origin::signed(1), token, 2));
+		assert_noop!(Erc20::mint(Origin::signed(1), token, 2, 10), Error::<Test>::AccountFrozen);
+		assert_ok!(Erc20::mint(Origin::signed(1), token, 3, 10));
+
+		assert_ok!(Erc20::pause(Origin::signed(1), token));
+		assert_noop!(Erc20::mint(Origin::signed(1), token, 3, 10), Error::<Test>::TokenPaused);
+
+		assert_ok!(Erc20::unpause(Origin::signed(1), token));
+		assert_ok!(Erc20::thaw(Origin::signed(1), token, 2));
+		assert_ok!(Erc20::mint(Origin::signed(1), token, 2, 10));
+		assert_eq!(Erc20::token_details(token).unwrap().total_supply, 120);
+	});
+}
+
+#[test]
+fn frozen_account_cannot_send_or_receive() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		let other = create_token(1, b"OTH", 100);
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 2, 30));
+
+		assert_noop!(Erc20::freeze(Origin::signed(2), token, 2), Error::<Test>::RequireIssuer);
+		assert_ok!(Erc20::freeze(Origin::signed(1), token, 2));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Frozen(token, 2, Some(1))));
+
+		assert_noop!(Erc20::transfer(Origin::signed(2), token, 3, 10), Error::<Test>::AccountFrozen);
+		assert_noop!(Erc20::transfer(Origin::signed(1), token, 2, 10), Error::<Test>::AccountFrozen);
+		assert_noop!(Erc20::burn(Origin::signed(2), token, 10), Error::<Test>::AccountFrozen);
+
+		// Other tokens are not affected
+		assert_ok!(Erc20::transfer(Origin::signed(1), other, 2, 10));
+
+		assert_ok!(Erc20::thaw(Origin::signed(1), token, 2));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Thawed(token, 2, Some(1))));
+		assert_ok!(Erc20::transfer(Origin::signed(2), token, 3, 10));
+	});
+}
+
+#[test]
+fn issuer_can_force_transfer_from_frozen_account() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 2, 30));
+
+		assert_noop!(Erc20::force_transfer(Origin::signed(1), token, 2, 3, 10), Error::<Test>::RequireFrozen);
+
+		assert_ok!(Erc20::freeze(Origin::signed(1), token, 2));
+		assert_ok!(Erc20::pause(Origin::signed(1), token));
+		assert_noop!(Erc20::force_transfer(Origin::signed(3), token, 2, 3, 10), Error::<Test>::RequireIssuer);
+		assert_noop!(Erc20::force_transfer(Origin::signed(1), token, 2, 3, 31), Error::<Test>::InsufficientBalance);
+
+		assert_ok!(Erc20::force_transfer(Origin::signed(1), token, 2, 3, 30));
+		assert_eq!(Erc20::balance_of(token, 2), 0);
+		assert_eq!(Erc20::balance_of(token, 3), 30);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::ForceTransferred(token, 2, 3, 30, Some(1))));
+	});
+}
+
+#[test]
+fn root_overrides_the_issuer() {
+	new_test_ext().execute_with(|| {
+		let token = create_token(1, b"TKN", 100);
+
+		assert_noop!(Erc20::pause(Origin::root(), 1), Error::<Test>::InvalidTokenId);
+		assert_noop!(Erc20::pause(Origin::none(), token), sp_runtime::traits::BadOrigin);
+
+		assert_ok!(Erc20::pause(Origin::root(), token));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::Paused(token, None)));
+		assert_ok!(Erc20::freeze(Origin::root(), token, 2));
+
+		// Root needs no freeze to move tokens
+		assert_ok!(Erc20::force_transfer(Origin::root(), token, 1, 2, 40));
+		assert_eq!(Erc20::balance_of(token, 2), 40);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::ForceTransferred(token, 1, 2, 40, None)));
+
+		assert_ok!(Erc20::unpause(Origin::root(), token));
+		assert_ok!(Erc20::thaw(Origin::root(), token, 2));
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 2, 10));
+	});
+}
+
+/// Token 0 issued by account 1, with a pool holding 1_000 currency and 2_000 tokens.
+fn create_funded_pool() -> (u32, u32) {
+	let token = create_token(1, b"TKN", 10_000);
+	assert_ok!(Erc20::create_pool(Origin::signed(1), token));
+	assert_ok!(Erc20::add_liquidity(Origin::signed(1), token, 1_000, 2_000, 1_000));
+	(token, Erc20::pool(token).expect("pool was created"))
+}
+
+#[test]
+fn create_pool_issues_an_lp_token() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Erc20::create_pool(Origin::signed(1), 0), Error::<Test>::InvalidTokenId);
+
+		let token = create_token(1, b"TKN", 100);
+		assert_ok!(Erc20::create_pool(Origin::signed(2), token));
+		let lp_token = Erc20::pool(token).unwrap();
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::PoolCreated(token, lp_token, 2)));
+		assert_eq!(Erc20::token_details(lp_token), Some(Erc20Token {
+			issuer: Erc20::pool_account(token),
+			name: b"Swap pool shares".to_vec(),
+			ticker: b"LP-TKN".to_vec(),
+			decimals: 18,
+			total_supply: 0,
+		}));
+
+		assert_noop!(Erc20::create_pool(Origin::signed(1), token), Error::<Test>::PoolExists);
+		// Nothing to trade against yet
+		assert_eq!(Erc20::quote_currency_to_tokens(token, 100), None);
+		assert_noop!(Erc20::swap_currency_for_tokens(Origin::signed(2), token, 100, 0), Error::<Test>::EmptyPool);
+	});
+}
+
+#[test]
+fn liquidity_is_added_at_the_pool_price() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Erc20::add_liquidity(Origin::signed(1), 0, 1_000, 2_000, 0), Error::<Test>::NoPool);
+
+		let (token, lp_token) = create_funded_pool();
+		assert_eq!(Erc20::reserves(token), (1_000, 2_000));
+		assert_eq!(Erc20::balance_of(lp_token, 1), 1_000);
+		assert_eq!(Balances::free_balance(1), 9_000);
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::LiquidityAdded(token, 1, 1_000, 2_000, 1_000)));
+
+		// 500 currency needs 1_000 tokens, plus one for rounding in favour of the pool
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 3, 2_000));
+		assert_noop!(Erc20::add_liquidity(Origin::signed(3), token, 500, 1_000, 0), Error::<Test>::SlippageExceeded);
+		assert_noop!(Erc20::add_liquidity(Origin::signed(3), token, 500, 1_001, 501), Error::<Test>::SlippageExceeded);
+		assert_ok!(Erc20::add_liquidity(Origin::signed(3), token, 500, 1_001, 500));
+
+		assert_eq!(Erc20::reserves(token), (1_500, 3_001));
+		assert_eq!(Erc20::balance_of(lp_token, 3), 500);
+		assert_eq!(Erc20::token_details(lp_token).unwrap().total_supply, 1_500);
+	});
+}
+
+#[test]
+fn add_liquidity_rounding_cannot_overflow() {
+	new_test_ext().execute_with(|| {
+		let (token, _) = create_funded_pool();
+		Reserves::<Test>::insert(token, (1, u64::MAX));
+
+		// The matching tokens are `u64::MAX`, rounding them up must not wrap to zero
+		assert_noop!(Erc20::add_liquidity(Origin::signed(3), token, 1, u64::MAX, 0), Error::<Test>::StorageOverflow);
+	});
+}
+
+#[test]
+fn liquidity_is_removed_pro_rata() {
+	new_test_ext().execute_with(|| {
+		let (token, lp_token) = create_funded_pool();
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, 3, 2_000));
+		assert_ok!(Erc20::add_liquidity(Origin::signed(3), token, 500, 1_001, 500));
+
+		assert_noop!(Erc20::remove_liquidity(Origin::signed(3), token, 0, 0, 0), Error::<Test>::ZeroAmount);
+		assert_noop!(Erc20::remove_liquidity(Origin::signed(3), token, 501, 0, 0), Error::<Test>::InsufficientBalance);
+		assert_noop!(Erc20::remove_liquidity(Origin::signed(3), token, 500, 0, 1_001), Error::<Test>::SlippageExceeded);
+
+		assert_ok!(Erc20::remove_liquidity(Origin::signed(3), token, 500, 500, 1_000));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::LiquidityRemoved(token, 3, 500, 1_000, 500)));
+		assert_eq!(Erc20::balance_of(token, 3), 1_999);
+		assert_eq!(Balances::free_balance(3), 10_000);
+		assert_eq!(Erc20::reserves(token), (1_000, 2_001));
+
+		// The last shares empty the pool
+		assert_ok!(Erc20::remove_liquidity(Origin::signed(1), token, 1_000, 1_000, 2_001));
+		assert_eq!(Erc20::reserves(token), (0, 0));
+		assert_eq!(Erc20::token_details(lp_token).unwrap().total_supply, 0);
+		assert_eq!(Erc20::quote_tokens_to_currency(token, 100), None);
+	});
+}
+
+#[test]
+fn swaps_follow_the_constant_product() {
+	new_test_ext().execute_with(|| {
+		let (token, _) = create_funded_pool();
+
+		// 1% of 100 is kept, 99 * 2_000 / 1_099 = 180
+		assert_eq!(Erc20::quote_currency_to_tokens(token, 100), Some(180));
+		assert_noop!(Erc20::swap_currency_for_tokens(Origin::signed(2), token, 100, 181), Error::<Test>::SlippageExceeded);
+		assert_ok!(Erc20::swap_currency_for_tokens(Origin::signed(2), token, 100, 180));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::CurrencySwapped(token, 2, 100, 180)));
+		assert_eq!(Erc20::balance_of(token, 2), 180);
+		assert_eq!(Balances::free_balance(2), 9_900);
+		assert_eq!(Erc20::reserves(token), (1_100, 1_820));
+
+		// Swapping back returns less than was put in, the rest stays with the pool
+		let currency_out = Erc20::quote_tokens_to_currency(token, 180).unwrap();
+		assert!(currency_out < 100);
+		assert_noop!(Erc20::swap_tokens_for_currency(Origin::signed(2), token, 181, 0), Error::<Test>::InsufficientBalance);
+		assert_noop!(Erc20::swap_tokens_for_currency(Origin::signed(2), token, 180, currency_out + 1), Error::<Test>::SlippageExceeded);
+		assert_ok!(Erc20::swap_tokens_for_currency(Origin::signed(2), token, 180, currency_out));
+		assert_eq!(last_event(), TestEvent::erc20(RawEvent::TokensSwapped(token, 2, 180, currency_out)));
+		assert_eq!(Erc20::balance_of(token, 2), 0);
+		assert_eq!(Balances::free_balance(2), 9_900 + currency_out);
+		assert_eq!(Erc20::reserves(token), (1_100 - currency_out, 2_000));
+	});
+}
+
+#[test]
+fn donations_do_not_move_the_price() {
+	new_test_ext().execute_with(|| {
+		let (token, _) = create_funded_pool();
+		let pool = Erc20::pool_account(token);
+		let quote = Erc20::quote_currency_to_tokens(token, 100);
+
+		assert_ok!(Balances::transfer(Origin::signed(2), pool, 5_000));
+		assert_ok!(Erc20::transfer(Origin::signed(1), token, pool, 3_000));
+
+		assert_eq!(Erc20::reserves(token), (1_000, 2_000));
+		assert_eq!(Erc20::quote_currency_to_tokens(token, 100), quote);
+	});
+}
+
+#[test]
+fn swaps_respect_pause_and_freeze() {
+	new_test_ext().execute_with(|| {
+		let (token, _) = create_funded_pool();
+
+		assert_ok!(Erc20::freeze(Origin::signed(1), token, 2));
+		assert_noop!(Erc20::swap_currency_for_tokens(Origin::signed(2), token, 100, 0), Error::<Test>::AccountFrozen);
+		assert_ok!(Erc20::pause(Origin::signed(1), token));
+		assert_noop!(Erc20::swap_currency_for_tokens(Origin::signed(3), token, 100, 0), Error::<Test>::TokenPaused);
+		// The failed swaps took no currency
+		assert_eq!(Balances::free_balance(2), 10_000);
+		assert_eq!(Balances::free_balance(3), 10_000);
+	});
+}
+
+#[test]
+fn pool_accounts_cannot_be_frozen_or_drained() {
+	new_test_ext().execute_with(|| {
+		let (token, lp_token) = create_funded_pool();
+		let pool = Erc20::pool_account(token);
+
+		assert_noop!(Erc20::freeze(Origin::signed(1), token, pool), Error::<Test>::PoolAccount);
+		assert_noop!(Erc20::freeze(Origin::root(), token, pool), Error::<Test>::PoolAccount);
+		assert_noop!(Erc20::force_transfer(Origin::signed(1), token, pool, 1, 2_000), Error::<Test>::PoolAccount);
+		assert_noop!(Erc20::force_transfer(Origin::root(), token, pool, 1, 2_000), Error::<Test>::PoolAccount);
+
+		// The providers can still take everything out
+		assert_ok!(Erc20::remove_liquidity(Origin::signed(1), token, 1_000, 1_000, 2_000));
+		assert_eq!(Erc20::balance_of(lp_token, 1), 0);
+		assert_eq!(Erc20::reserves(token), (0, 0));
+	});
+}
+
+/// A dispatchable that may change an allowance or a balance.
+#[derive(Clone, Copy, Debug)]
+enum Op {
+	Approve { owner: u64, spender: u64, value: u64 },
+	IncreaseAllowance { owner: u64, spender: u64, value: u64 },
+	DecreaseAllowance { owner: u64, spender: u64, value: u64 },
+	TransferFrom { spender: u64, from: u64, value: u64 },
+	BurnFrom { spender: u64, from: u64, value: u64 },
+	Transfer { from: u64, value: u64 },
+}
+
+/// Account 1 holds the whole supply of 100, account 3 receives every transfer and
+/// tries to spend an allowance it was never given.
+const OPS: [Op; 10] = [
+	Op::Approve { owner: 1, spender: 2, value: 50 },
+	Op::Approve { owner: 2, spender: 1, value: 50 },
+	Op::IncreaseAllowance { owner: 1, spender: 2, value: 30 },
+	Op::IncreaseAllowance { owner: 1, spender: 2, value: u64::max_value() },
+	Op::DecreaseAllowance { owner: 1, spender: 2, value: 30 },
+	Op::TransferFrom { spender: 2, from: 1, value: 40 },
+	Op::TransferFrom { spender: 3, from: 1, value: 10 },
+	Op::TransferFrom { spender: 1, from: 2, value: 10 },
+	Op::BurnFrom { spender: 2, from: 1, value: 40 },
+	Op::Transfer { from: 1, value: 60 },
+];
+
+/// Every sequence of four ops, checked against a model of the allowances and
+/// balances: an allowance changes only by its owner or by its spender moving as much,
+/// and the total supply always matches the balances.
+#[test]
+fn allowances_match_a_model_over_every_op_sequence() {
+	let depth = 4;
+	for sequence in 0..OPS.len().pow(depth) {
+		new_test_ext().execute_with(|| {
+			let token = create_token(1, b"TKN", 100);
+			let mut allowances: BTreeMap<(u64, u64), u64> = BTreeMap::new();
+			let mut balances: BTreeMap<u64, u64> = BTreeMap::new();
+			balances.insert(1, 100);
+
+			let mut digits = sequence;
+			let mut ops = Vec::new();
+			for _ in 0..depth {
+				let op = OPS[digits % OPS.len()];
+				digits /= OPS.len();
+				ops.push(op);
+
+				let (result, expected) = match op {
+					Op::Approve { owner, spender, value } => {
+						allowances.insert((owner, spender), value);
+						(Erc20::approve(Origin::signed(owner), token, spender, value), true)
+					},
+					Op::IncreaseAllowance { owner, spender, value } => {
+						let allowance = allowances.entry((owner, spender)).or_default();
+						let expected = match allowance.checked_add(value) {
+							Some(updated) => { *allowance = updated; true },
+							None => false,
+						};
+						(Erc20::increase_allowance(Origin::signed(owner), token, spender, value), expected)
+					},
+					Op::DecreaseAllowance { owner, spender, value } => {
+						let allowance = allowances.entry((owner, spender)).or_default();
+						let expected = match allowance.checked_sub(value) {
+							Some(updated) => { *allowance = updated; true },
+							None => false,
+						};
+						(Erc20::decrease_allowance(Origin::signed(owner), token, spender, value), expected)
+					},
+					Op::TransferFrom { spender, from, value } | Op::BurnFrom { spender, from, value } => {
+						let allowance = allowances.entry((from, spender)).or_default();
+						let balance = balances.entry(from).or_default();
+						let expected = *allowance >= value && *balance >= value;
+						if expected {
+							*allowance -= value;
+							*balance -= value;
+						}
+						let result = if let Op::TransferFrom { .. } = op {
+							if expected {
+								*balances.entry(3).or_default() += value;
+							}
+							Erc20::transfer_from(Origin::signed(spender), token, from, 3, value)
+						} else {
+							Erc20::burn_from(Origin::signed(spender), token, from, value)
+						};
+						(result, expected)
+					},
+					Op::Transfer { from, value } => {
+						let balance = balances.entry(from).or_default();
+						let expected = *balance >= value;
+						if expected {
+							*balance -= value;
+							*balances.entry(3).or_default() += value;
+						}
+						(Erc20::transfer(Origin::signed(from), token, 3, value), expected)
+					},
+				};
+
+				assert_eq!(result.is_ok(), expected, "{:?}", ops);
+				for owner in 1..=3 {
+					assert_eq!(Erc20::balance_of(token, owner), balances.get(&owner).copied().unwrap_or_default(), "{:?}", ops);
+					for spender in 1..=3 {
+						assert_eq!(
+							Erc20::allowance(token, (owner, spender)),
+							allowances.get(&(owner, spender)).copied().unwrap_or_default(),
+							"{:?}", ops
+						);
+					}
+				}
+				assert_eq!(Erc20::token_details(token).unwrap().total_supply, balances.values().sum::<u64>(), "{:?}", ops);
+			}
+		});
+	}
+}
@@ -0,0 +1,166 @@
+//! Weights for pallet_erc20
+//!
+//! Only the storage reads and writes of each call are charged, counted by hand from the
+//! worst case set up in `benchmarking.rs`. Execution time is not included until the
+//! weights are generated from a benchmark run: build the node with
+//! `--features runtime-benchmarks` and run the command below. The name and ticker
+//! lengths of `create_token` are then priced as well; until then they are only bounded
+//! by `MAX_NAME_LEN` and `MAX_TICKER_LEN`.
+
+// ./target/release/node-template
+// benchmark
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=erc20
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/erc20/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by pallet_erc20.
+/// `n` and `t` are the name and ticker lengths of a new token, in bytes.
+pub trait WeightInfo {
+    fn create_token(n: u32, t: u32, ) -> Weight;
+    fn transfer() -> Weight;
+    fn approve() -> Weight;
+    fn transfer_from() -> Weight;
+    fn mint() -> Weight;
+    fn burn() -> Weight;
+    fn burn_from() -> Weight;
+    fn transfer_ownership() -> Weight;
+    fn pause() -> Weight;
+    fn unpause() -> Weight;
+    fn freeze() -> Weight;
+    fn thaw() -> Weight;
+    fn force_transfer() -> Weight;
+    fn create_pool() -> Weight;
+    fn add_liquidity() -> Weight;
+    fn remove_liquidity() -> Weight;
+    fn swap_currency_for_tokens() -> Weight;
+    fn swap_tokens_for_currency() -> Weight;
+}
+
+/// Weights for pallet_erc20 using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+    fn create_token(_n: u32, _t: u32, ) -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 3 as Weight)
+    }
+    fn transfer() -> Weight {
+        T::DbWeight::get().reads_writes(6 as Weight, 2 as Weight)
+    }
+    fn approve() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn transfer_from() -> Weight {
+        T::DbWeight::get().reads_writes(7 as Weight, 3 as Weight)
+    }
+    fn mint() -> Weight {
+        T::DbWeight::get().reads_writes(5 as Weight, 2 as Weight)
+    }
+    fn burn() -> Weight {
+        T::DbWeight::get().reads_writes(4 as Weight, 2 as Weight)
+    }
+    fn burn_from() -> Weight {
+        T::DbWeight::get().reads_writes(5 as Weight, 3 as Weight)
+    }
+    fn transfer_ownership() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn pause() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn unpause() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn freeze() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn thaw() -> Weight {
+        T::DbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn force_transfer() -> Weight {
+        T::DbWeight::get().reads_writes(4 as Weight, 2 as Weight)
+    }
+    fn create_pool() -> Weight {
+        T::DbWeight::get().reads_writes(3 as Weight, 4 as Weight)
+    }
+    fn add_liquidity() -> Weight {
+        T::DbWeight::get().reads_writes(16 as Weight, 7 as Weight)
+    }
+    fn remove_liquidity() -> Weight {
+        T::DbWeight::get().reads_writes(16 as Weight, 7 as Weight)
+    }
+    fn swap_currency_for_tokens() -> Weight {
+        T::DbWeight::get().reads_writes(11 as Weight, 5 as Weight)
+    }
+    fn swap_tokens_for_currency() -> Weight {
+        T::DbWeight::get().reads_writes(11 as Weight, 5 as Weight)
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create_token(_n: u32, _t: u32, ) -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 3 as Weight)
+    }
+    fn transfer() -> Weight {
+        RocksDbWeight::get().reads_writes(6 as Weight, 2 as Weight)
+    }
+    fn approve() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn transfer_from() -> Weight {
+        RocksDbWeight::get().reads_writes(7 as Weight, 3 as Weight)
+    }
+    fn mint() -> Weight {
+        RocksDbWeight::get().reads_writes(5 as Weight, 2 as Weight)
+    }
+    fn burn() -> Weight {
+        RocksDbWeight::get().reads_writes(4 as Weight, 2 as Weight)
+    }
+    fn burn_from() -> Weight {
+        RocksDbWeight::get().reads_writes(5 as Weight, 3 as Weight)
+    }
+    fn transfer_ownership() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn pause() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn unpause() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn freeze() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn thaw() -> Weight {
+        RocksDbWeight::get().reads_writes(1 as Weight, 1 as Weight)
+    }
+    fn force_transfer() -> Weight {
+        RocksDbWeight::get().reads_writes(4 as Weight, 2 as Weight)
+    }
+    fn create_pool() -> Weight {
+        RocksDbWeight::get().reads_writes(3 as Weight, 4 as Weight)
+    }
+    fn add_liquidity() -> Weight {
+        RocksDbWeight::get().reads_writes(16 as Weight, 7 as Weight)
+    }
+    fn remove_liquidity() -> Weight {
+        RocksDbWeight::get().reads_writes(16 as Weight, 7 as Weight)
+    }
+    fn swap_currency_for_tokens() -> Weight {
+        RocksDbWeight::get().reads_writes(11 as Weight, 5 as Weight)
+    }
+    fn swap_tokens_for_currency() -> Weight {
+        RocksDbWeight::get().reads_writes(11 as Weight, 5 as Weight)
+    }
+}
@@ -9,7 +9,7 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
@@ -59,6 +59,9 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of an erc20 pallet token.
+pub type TokenId = u32;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -263,9 +266,19 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const Erc20ModuleId: ModuleId = ModuleId(*b"py/erc20");
+	pub const SwapFee: Permill = Permill::from_parts(3_000);
+}
+
 impl erc20::Trait for Runtime {
 	type Event = Event;
-	type TokenBalance = u64;
+	type TokenBalance = Balance;
+	type TokenId = TokenId;
+	type Currency = Balances;
+	type ModuleId = Erc20ModuleId;
+	type SwapFee = SwapFee;
+	type WeightInfo = erc20::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -399,6 +412,36 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_erc20_rpc_runtime_api::Erc20Api<Block, AccountId, TokenId, Balance> for Runtime {
+		fn balance_of(token_id: TokenId, who: AccountId) -> Balance {
+			Erc20::balance_of(token_id, who)
+		}
+
+		fn allowance(token_id: TokenId, owner: AccountId, spender: AccountId) -> Balance {
+			Erc20::allowance(token_id, (owner, spender))
+		}
+
+		fn token_info(token_id: TokenId) -> Option<pallet_erc20_rpc_runtime_api::TokenInfo<AccountId, TokenId, Balance>> {
+			Erc20::token_info(token_id)
+		}
+
+		fn holders(
+			token_id: TokenId,
+			cursor: Option<AccountId>,
+			limit: u32,
+		) -> pallet_erc20_rpc_runtime_api::HoldersPage<AccountId, Balance> {
+			Erc20::holders(token_id, cursor, limit)
+		}
+
+		fn quote_currency_to_tokens(token_id: TokenId, currency_in: Balance) -> Option<Balance> {
+			Erc20::quote_currency_to_tokens(token_id, currency_in)
+		}
+
+		fn quote_tokens_to_currency(token_id: TokenId, tokens_in: Balance) -> Option<Balance> {
+			Erc20::quote_tokens_to_currency(token_id, tokens_in)
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
@@ -424,4 +467,38 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			pallet: Vec<u8>,
+			benchmark: Vec<u8>,
+			lowest_range_values: Vec<u32>,
+			highest_range_values: Vec<u32>,
+			steps: Vec<u32>,
+			repeat: u32,
+			extra: bool,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark};
+
+			let whitelist: Vec<Vec<u8>> = vec![
+				// Block Number
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec(),
+				// Execution Phase
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec(),
+				// Event Count
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec(),
+				// System Events
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec(),
+			];
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat, &whitelist, extra);
+
+			add_benchmark!(params, batches, b"erc20", Erc20);
+
+			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+			Ok(batches)
+		}
+	}
 }
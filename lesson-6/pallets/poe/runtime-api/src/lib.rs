@@ -0,0 +1,17 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait PoeApi<Hash> where
+        Hash: Codec,
+    {
+        /// Whether `leaf` is in the tree of the anchored `root`. The tree hashes a leaf
+        /// as `hash((0u8, leaf))` and a node as `hash((1u8, left, right))`, with the
+        /// children in sorted order. `proof` holds the sibling hashes from the leaf up.
+        fn verify_merkle_proof(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool;
+    }
+}
@@ -1,11 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage,
-	ensure, dispatch, traits::{Get}
+	ensure, dispatch, transactional, storage::migration::StorageIterator,
+	traits::{BalanceStatus, Currency, Get, ReservableCurrency}, weights::Weight,
 };
 use frame_system::ensure_signed;
-// use sp_std::vec::Vec;
+use sp_runtime::{traits::{Hash as HashT, Saturating}, RuntimeDebug};
 use sp_std::prelude::*;
 
 #[cfg(test)]
@@ -14,13 +16,88 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
 /// Configure the pallet by specifying the parameters and types on which it depends.
 pub trait Trait: frame_system::Trait {
 	/// Because this pallet emits events, it depends on the runtime's definition of an event.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
-	// u32 max length
-	type MaxClaimLength: Get<u32>;
+	/// The currency claim deposits are reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Deposit reserved for every claim.
+	type ClaimDeposit: Get<BalanceOf<Self>>;
+
+	/// Extra deposit for each byte of claim metadata.
+	type DepositPerByte: Get<BalanceOf<Self>>;
+
+	/// Previous owners kept for each claim; the oldest is dropped beyond this.
+	type MaxHistoryLength: Get<u32>;
+
+	/// Longest MIME type of a claim, in bytes.
+	type MaxMimeTypeLength: Get<u32>;
+
+	/// Longest description of a claim, in bytes.
+	type MaxDescriptionLength: Get<u32>;
+
+	/// Most claims expiring at the same block, so pruning them fits in `on_initialize`.
+	type MaxExpiriesPerBlock: Get<u32>;
+
+	/// Most claims created by one `create_claims_batch`.
+	type MaxBatchSize: Get<u32>;
+}
+
+/// Optional details about the claimed content.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimMetadata {
+	/// e.g. `application/pdf`.
+	pub mime_type: Option<Vec<u8>>,
+	pub description: Option<Vec<u8>>,
+}
+
+impl ClaimMetadata {
+	/// Bytes of metadata the deposit is charged for.
+	pub fn len(&self) -> usize {
+		self.mime_type.as_ref().map_or(0, Vec::len) + self.description.as_ref().map_or(0, Vec::len)
+	}
+}
+
+/// Layouts of this pallet's storage, in the order they were released.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// `Proofs` maps the claimed bytes to their owner and block.
+	V1_0_0,
+	/// `Proofs` maps a content digest to its `Proof`.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+/// A claim on a content digest.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Proof<AccountId, BlockNumber, Balance> {
+	pub owner: AccountId,
+	/// The block of the claim, or of its last transfer.
+	pub block_number: BlockNumber,
+	/// The claim is pruned at the start of this block.
+	pub expires: Option<BlockNumber>,
+	pub metadata: ClaimMetadata,
+	/// Reserved from the owner, returned when the claim is revoked or expires.
+	pub deposit: Balance,
+}
+
+/// The root of a Merkle tree of content digests, standing in for a claim on each of them.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Anchor<AccountId, BlockNumber, Balance> {
+	pub owner: AccountId,
+	pub block_number: BlockNumber,
+	/// Reserved from the owner, returned when the root is revoked.
+	pub deposit: Balance,
 }
 
 // The pallet's runtime storage items.
@@ -31,8 +108,16 @@ decl_storage! {
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as TemplateModule {
 		/// The storage item for our proofs.
-		/// It maps a proof to the user who made the claim and when they made it.
-		Proofs: map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		/// It maps the digest of the content to its claim.
+		Proofs get(fn proofs): map hasher(blake2_128_concat) T::Hash => Option<Proof<T::AccountId, T::BlockNumber, BalanceOf<T>>>;
+		/// Previous owners of each claim and the block they got it at, oldest first.
+		History get(fn history): map hasher(blake2_128_concat) T::Hash => Vec<(T::AccountId, T::BlockNumber)>;
+		/// Claims expiring at each block.
+		Expiries get(fn expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<T::Hash>;
+		/// Anchored Merkle roots.
+		MerkleRoots get(fn merkle_roots): map hasher(blake2_128_concat) T::Hash => Option<Anchor<T::AccountId, T::BlockNumber, BalanceOf<T>>>;
+		/// The layout the storage is in. New chains start on the latest one.
+		pub StorageVersion get(fn storage_version) build(|_| Releases::V2_0_0): Releases;
 	}
 }
 
@@ -42,11 +127,20 @@ decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as frame_system::Trait>::AccountId,
+		Hash = <T as frame_system::Trait>::Hash,
 	{
 		/// Event emitted when a proof has been claimed. [who, claim]
-		ClaimCreated(AccountId, Vec<u8>),
+		ClaimCreated(AccountId, Hash),
 		/// Event emitted when a claim is revoked by the owner. [who, claim]
-		ClaimRevoked(AccountId, Vec<u8>),
+		ClaimRevoked(AccountId, Hash),
+		/// Event emitted when a claim changes hands. [from, to, claim]
+		ClaimTransferred(AccountId, AccountId, Hash),
+		/// Event emitted when an expired claim is pruned. [owner, claim]
+		ClaimExpired(AccountId, Hash),
+		/// Event emitted when a Merkle root is anchored. [who, root]
+		MerkleRootAnchored(AccountId, Hash),
+		/// Event emitted when a Merkle root is revoked by the owner. [who, root]
+		MerkleRootRevoked(AccountId, Hash),
 	}
 );
 
@@ -56,7 +150,19 @@ decl_error! {
 		ProofAlreadyExist,
 		ClaimNotExist,
 		NotClaimOwner,
-		ProofTooLong
+		MimeTypeTooLong,
+		DescriptionTooLong,
+		/// The expiry block must be after the current one.
+		ExpiryInPast,
+		/// Too many claims already expire at that block.
+		TooManyExpiries,
+		/// The claim already belongs to the destination.
+		TransferToSelf,
+		/// A batch must hold between one and `MaxBatchSize` claims.
+		InvalidBatchSize,
+		RootAlreadyAnchored,
+		RootNotExist,
+		NotRootOwner,
 	}
 }
 
@@ -71,52 +177,131 @@ decl_module! {
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if Self::storage_version() == Releases::V1_0_0 {
+				weight = weight.saturating_add(Self::migrate_to_v2());
+			}
+			weight
+		}
+
+		/// Prune the claims expiring at this block.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expired = Expiries::<T>::take(now);
+			let mut pruned = 0;
+			for claim in expired.iter() {
+				// Claims revoked since are gone already.
+				if let Some(proof) = Proofs::<T>::take(claim) {
+					History::<T>::remove(claim);
+					T::Currency::unreserve(&proof.owner, proof.deposit);
+					Self::deposit_event(RawEvent::ClaimExpired(proof.owner, *claim));
+					pruned += 1;
+				}
+			}
+			T::DbWeight::get().reads_writes(1 + 2 * expired.len() as Weight, 1 + 3 * pruned)
+		}
+
 		/// Allow a user to claim ownership of an unclaimed proof.
-		#[weight = 0]
-		pub fn create_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+		///
+		/// Reserves `ClaimDeposit` plus `DepositPerByte` for each byte of metadata.
+		#[weight = <Module<T>>::create_claim_weight()]
+		pub fn create_claim(
+			origin,
+			claim: T::Hash,
+			metadata: ClaimMetadata,
+			expires: Option<T::BlockNumber>,
+		) -> dispatch::DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://substrate.dev/docs/en/knowledgebase/runtime/origin
 			let sender = ensure_signed(origin)?;
 
-			// Verify that the specified proof has not already been claimed.
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			Self::do_create_claim(&sender, claim, metadata, expires)
+		}
+
+		/// Claim several proofs at once, all expiring at `expires`. Either every claim
+		/// is created or none is.
+		#[weight = <Module<T>>::create_claim_weight().saturating_mul(claims.len() as Weight)]
+		#[transactional]
+		pub fn create_claims_batch(
+			origin,
+			claims: Vec<(T::Hash, ClaimMetadata)>,
+			expires: Option<T::BlockNumber>,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(
+				!claims.is_empty() && claims.len() as u32 <= T::MaxBatchSize::get(),
+				Error::<T>::InvalidBatchSize
+			);
+
+			for (claim, metadata) in claims {
+				Self::do_create_claim(&sender, claim, metadata, expires)?;
+			}
+
+			Ok(())
+		}
+
+		/// Anchor the root of a Merkle tree of content digests, reserving `ClaimDeposit`.
+		/// Each digest can then be proven with `verify_merkle_proof`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 2)]
+		pub fn anchor_merkle_root(origin, root: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!MerkleRoots::<T>::contains_key(&root), Error::<T>::RootAlreadyAnchored);
 
-			// ensure max length
-			ensure!(T::MaxClaimLength::get() >= claim.len() as u32, Error::<T>::ProofTooLong);
+			let deposit = T::ClaimDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
 
-			// Get the block number from the FRAME System module.
-			// let current_block = <frame_system::Module<T>>::block_number();
+			MerkleRoots::<T>::insert(&root, Anchor {
+				owner: sender.clone(),
+				block_number: frame_system::Module::<T>::block_number(),
+				deposit,
+			});
 
-			// Store the proof with the sender and block number.
-			// Proofs::<T>::insert(&proof, (&sender, current_block));
-			Proofs::<T>::insert(&claim, (sender.clone(), frame_system::Module::<T>::block_number()));
+			Self::deposit_event(RawEvent::MerkleRootAnchored(sender, root));
 
-			// Emit an event that the claim was created.
-			Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+			Ok(())
+		}
+
+		/// Allow the owner to revoke an anchored root, returning its deposit.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn revoke_merkle_root(origin, root: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let anchor = MerkleRoots::<T>::get(&root).ok_or(Error::<T>::RootNotExist)?;
+
+			ensure!(sender == anchor.owner, Error::<T>::NotRootOwner);
+
+			MerkleRoots::<T>::remove(&root);
+			T::Currency::unreserve(&sender, anchor.deposit);
+
+			Self::deposit_event(RawEvent::MerkleRootRevoked(sender, root));
 
 			Ok(())
 		}
 
 		/// Allow the owner to revoke their claim.
-		#[weight = 0]
-		pub fn revoke_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 4)]
+		pub fn revoke_claim(origin, claim: T::Hash) -> dispatch::DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://substrate.dev/docs/en/knowledgebase/runtime/origin
 			let sender = ensure_signed(origin)?;
 
 			// Verify that the specified proof has been claimed.
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
-
-			// Get owner of the claim.
-			let (owner, _) = Proofs::<T>::get(&claim);
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
 			// Verify that sender of the current call is the claim owner.
-			ensure!(sender == owner, Error::<T>::NotClaimOwner);
+			ensure!(sender == proof.owner, Error::<T>::NotClaimOwner);
 
-			// Remove claim from storage.
+			// Remove claim from storage and return the deposit.
 			Proofs::<T>::remove(&claim);
+			History::<T>::remove(&claim);
+			if let Some(expires) = proof.expires {
+				Expiries::<T>::mutate(expires, |expiring| expiring.retain(|c| *c != claim));
+			}
+			T::Currency::unreserve(&sender, proof.deposit);
 
 			// Emit an event that the claim was erased.
 			Self::deposit_event(RawEvent::ClaimRevoked(sender, claim));
@@ -124,19 +309,142 @@ decl_module! {
 			Ok(())
 		}
 
-		#[weight = 0]
-		pub fn transfer_claim(origin, claim: Vec<u8>, dest: T::AccountId) -> dispatch::DispatchResult {
+		/// Give a claim to `dest`, along with its deposit.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 4)]
+		pub fn transfer_claim(origin, claim: T::Hash, dest: T::AccountId) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let mut proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(proof.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(proof.owner != dest, Error::<T>::TransferToSelf);
 
-			let (owner, _) = Proofs::<T>::get(&claim);
+			// The deposit stays reserved, now on the new owner's account.
+			let unmoved = T::Currency::repatriate_reserved(&sender, &dest, proof.deposit, BalanceStatus::Reserved)?;
+			proof.deposit = proof.deposit.saturating_sub(unmoved);
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			History::<T>::mutate(&claim, |history| {
+				history.push((sender.clone(), proof.block_number));
+				let excess = history.len().saturating_sub(T::MaxHistoryLength::get() as usize);
+				history.drain(..excess);
+			});
 
-			Proofs::<T>::insert(&claim, (dest, frame_system::Module::<T>::block_number()));
+			proof.owner = dest.clone();
+			proof.block_number = frame_system::Module::<T>::block_number();
+			Proofs::<T>::insert(&claim, proof);
+
+			Self::deposit_event(RawEvent::ClaimTransferred(sender, dest, claim));
 
 			Ok(())
 		}
 	}
 }
+
+impl<T: Trait> Module<T> {
+	/// Re-key each v1 claim by the digest of its claimed bytes. V1 claims reserved no
+	/// deposit and had no expiry or metadata. Returns the weight consumed.
+	fn migrate_to_v2() -> Weight {
+		// V1 and v2 keys share the `Proofs` prefix, so drain every v1 entry before
+		// inserting the first v2 one.
+		let claims: Vec<_> = StorageIterator::<(T::AccountId, T::BlockNumber)>::new(b"TemplateModule", b"Proofs")
+			.drain()
+			.collect();
+		let migrated = claims.len() as Weight;
+		for (key, (owner, block_number)) in claims {
+			// The key is the blake2_128 hash of the encoded claim followed by the claim.
+			let claim = match key.get(16..).and_then(|mut encoded| Vec::<u8>::decode(&mut encoded).ok()) {
+				Some(claim) => claim,
+				None => continue,
+			};
+			let proof = Proof { owner, block_number, expires: None, metadata: Default::default(), deposit: 0u32.into() };
+			Proofs::<T>::insert(T::Hashing::hash(&claim), proof);
+		}
+		StorageVersion::put(Releases::V2_0_0);
+
+		T::DbWeight::get().reads_writes(migrated, 2 * migrated + 1)
+	}
+
+	/// Weight of one claim with an expiry: the proof, the expiries of its block, the
+	/// block number and the owner's account.
+	fn create_claim_weight() -> Weight {
+		10_000 + T::DbWeight::get().reads_writes(4, 3)
+	}
+
+	fn do_create_claim(
+		sender: &T::AccountId,
+		claim: T::Hash,
+		metadata: ClaimMetadata,
+		expires: Option<T::BlockNumber>,
+	) -> dispatch::DispatchResult {
+		// Verify that the specified proof has not already been claimed.
+		ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+
+		Self::ensure_metadata(&metadata)?;
+
+		// Get the block number from the FRAME System module.
+		let current_block = frame_system::Module::<T>::block_number();
+
+		if let Some(expires) = expires {
+			ensure!(expires > current_block, Error::<T>::ExpiryInPast);
+			ensure!(
+				(Expiries::<T>::decode_len(expires).unwrap_or(0) as u32) < T::MaxExpiriesPerBlock::get(),
+				Error::<T>::TooManyExpiries
+			);
+		}
+
+		// Take the deposit last, so a failed reserve leaves no storage changes.
+		let deposit = Self::deposit_for(&metadata);
+		T::Currency::reserve(sender, deposit)?;
+
+		if let Some(expires) = expires {
+			Expiries::<T>::append(expires, claim);
+		}
+
+		// Store the proof with the sender and block number.
+		Proofs::<T>::insert(&claim, Proof {
+			owner: sender.clone(),
+			block_number: current_block,
+			expires,
+			metadata,
+			deposit,
+		});
+
+		// Emit an event that the claim was created.
+		Self::deposit_event(RawEvent::ClaimCreated(sender.clone(), claim));
+
+		Ok(())
+	}
+
+	/// Whether `leaf` is in the tree of the anchored `root`. `proof` holds the sibling
+	/// hashes from the leaf up; each pair is hashed in sorted order, so no left/right
+	/// flags are needed.
+	///
+	/// Leaves and nodes are hashed with different prefixes, so a node can't be passed
+	/// off as a leaf.
+	pub fn verify_merkle_proof(root: T::Hash, leaf: T::Hash, proof: Vec<T::Hash>) -> bool {
+		if !MerkleRoots::<T>::contains_key(&root) {
+			return false;
+		}
+		let computed = proof.into_iter().fold(T::Hashing::hash_of(&(0u8, leaf)), |node, sibling| {
+			if node <= sibling {
+				T::Hashing::hash_of(&(1u8, node, sibling))
+			} else {
+				T::Hashing::hash_of(&(1u8, sibling, node))
+			}
+		});
+		computed == root
+	}
+
+	/// Deposit reserved for a claim with `metadata`.
+	pub fn deposit_for(metadata: &ClaimMetadata) -> BalanceOf<T> {
+		let bytes = BalanceOf::<T>::from(metadata.len() as u32);
+		T::ClaimDeposit::get().saturating_add(T::DepositPerByte::get().saturating_mul(bytes))
+	}
+
+	fn ensure_metadata(metadata: &ClaimMetadata) -> dispatch::DispatchResult {
+		let too_long = |field: &Option<Vec<u8>>, max: u32| field.as_ref().map_or(false, |f| f.len() as u32 > max);
+		ensure!(!too_long(&metadata.mime_type, T::MaxMimeTypeLength::get()), Error::<T>::MimeTypeTooLong);
+		ensure!(!too_long(&metadata.description, T::MaxDescriptionLength::get()), Error::<T>::DescriptionTooLong);
+		Ok(())
+	}
+}
@@ -1,6 +1,6 @@
 use crate::{Module, Trait};
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::{RocksDbWeight, Weight}};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
@@ -10,6 +10,18 @@ impl_outer_origin! {
 	pub enum Origin for Test {}
 }
 
+mod poe {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		poe<T>,
+	}
+}
+
 // Configure a mock runtime to test the pallet.
 
 #[derive(Clone, Eq, PartialEq)]
@@ -32,10 +44,10 @@ impl system::Trait for Test {
 	type AccountId = u64;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type BlockExecutionWeight = ();
 	type ExtrinsicBaseWeight = ();
 	type MaximumExtrinsicWeight = MaximumBlockWeight;
@@ -43,24 +55,63 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
 
 parameter_types! {
-	pub const MaxClaimLength: u32 = 6;
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ClaimDeposit: u64 = 10;
+	pub const DepositPerByte: u64 = 1;
+	pub const MaxHistoryLength: u32 = 2;
+	pub const MaxMimeTypeLength: u32 = 16;
+	pub const MaxDescriptionLength: u32 = 32;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const MaxBatchSize: u32 = 3;
 }
 
 impl Trait for Test {
-	type Event = ();
-	type MaxClaimLength = MaxClaimLength;
+	type Event = TestEvent;
+	type Currency = Balances;
+	type ClaimDeposit = ClaimDeposit;
+	type DepositPerByte = DepositPerByte;
+	type MaxHistoryLength = MaxHistoryLength;
+	type MaxMimeTypeLength = MaxMimeTypeLength;
+	type MaxDescriptionLength = MaxDescriptionLength;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
 }
 
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
 pub type PoeModule = Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 5)],
+	}.assimilate_storage(&mut t).unwrap();
+
+	crate::GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not deposited at block 0.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
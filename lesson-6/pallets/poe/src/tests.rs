@@ -1,25 +1,43 @@
 use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{
+	assert_ok, assert_noop, Blake2_128Concat, StorageHasher,
+	storage::migration::{get_storage_value, put_storage_value},
+	traits::{OnInitialize, OnRuntimeUpgrade}, weights::{GetDispatchInfo, constants::RocksDbWeight},
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 use super::*;
 
+fn last_event() -> TestEvent {
+	System::events().pop().expect("an event was deposited").event
+}
+
 #[test]
 fn create_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
 
-		assert_eq!(Proofs::<Test>::get(&claim), (1, frame_system::Module::<Test>::block_number()));
+		assert_eq!(Proofs::<Test>::get(&claim), Some(Proof {
+			owner: 1,
+			block_number: System::block_number(),
+			expires: None,
+			metadata: Default::default(),
+			deposit: 10,
+		}));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimCreated(1, claim)));
 	})
 }
 
 #[test]
 fn create_claim_failed_when_claim_already_exist() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None);
 
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
+			PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None),
 			Error::<Test>::ProofAlreadyExist
 		);
 	})
@@ -28,11 +46,11 @@ fn create_claim_failed_when_claim_already_exist() {
 #[test]
 fn revoke_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None);
 
 		assert_ok!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone())
+			PoeModule::revoke_claim(Origin::signed(1), claim)
 		);
 	})
 }
@@ -40,10 +58,10 @@ fn revoke_claim_works() {
 #[test]
 fn revoke_claim_failed_when_claim_is_not_exist() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = H256::repeat_byte(1);
 
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(1), claim),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -52,11 +70,11 @@ fn revoke_claim_failed_when_claim_is_not_exist() {
 #[test]
 fn remove_claim_failed_when_not_the_owner() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None);
 
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(2), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(2), claim),
 			Error::<Test>::NotClaimOwner
 		);
 	})
@@ -65,24 +83,24 @@ fn remove_claim_failed_when_not_the_owner() {
 #[test]
 fn transfer_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None);
 		let dest = 2;
 
-		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), dest));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, dest));
 
-		assert_eq!(Proofs::<Test>::get(&claim), (dest, frame_system::Module::<Test>::block_number()));
+		assert_eq!(Proofs::<Test>::get(&claim).map(|proof| proof.owner), Some(dest));
 	})
 }
 
 #[test]
 fn transfer_claim_failed_when_claim_is_not_exist() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = H256::repeat_byte(1);
 		let dest = 2;
 
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(1), claim.clone(), dest),
+			PoeModule::transfer_claim(Origin::signed(1), claim, dest),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -91,13 +109,430 @@ fn transfer_claim_failed_when_claim_is_not_exist() {
 #[test]
 fn transfer_claim_failed_when_not_the_owner() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None);
 		let dest = 2;
 
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(2), claim.clone(), dest),
+			PoeModule::transfer_claim(Origin::signed(2), claim, dest),
 			Error::<Test>::NotClaimOwner
 		);
 	})
-}
\ No newline at end of file
+}
+
+#[test]
+fn create_claim_stores_metadata() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		let metadata = ClaimMetadata {
+			mime_type: Some(b"text/plain".to_vec()),
+			description: Some(b"my notes".to_vec()),
+		};
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, metadata.clone(), None));
+
+		assert_eq!(Proofs::<Test>::get(&claim).map(|proof| proof.metadata), Some(metadata));
+	})
+}
+
+#[test]
+fn create_claim_failed_when_metadata_too_long() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		let mime_type = ClaimMetadata { mime_type: Some(vec![0; 17]), description: None };
+		let description = ClaimMetadata { mime_type: None, description: Some(vec![0; 33]) };
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, mime_type, None),
+			Error::<Test>::MimeTypeTooLong
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, description, None),
+			Error::<Test>::DescriptionTooLong
+		);
+	})
+}
+
+#[test]
+fn create_claim_failed_when_expiry_in_past() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		let claim = H256::repeat_byte(1);
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, Default::default(), Some(5)),
+			Error::<Test>::ExpiryInPast
+		);
+	})
+}
+
+#[test]
+fn create_claim_failed_when_too_many_expiries() {
+	new_test_ext().execute_with(|| {
+		for i in 1..=2 {
+			assert_ok!(PoeModule::create_claim(Origin::signed(1), H256::repeat_byte(i), Default::default(), Some(10)));
+		}
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), H256::repeat_byte(3), Default::default(), Some(10)),
+			Error::<Test>::TooManyExpiries
+		);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), H256::repeat_byte(3), Default::default(), Some(11)));
+	})
+}
+
+#[test]
+fn expired_claims_are_pruned() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		let kept = H256::repeat_byte(2);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), Some(3)));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), kept, Default::default(), Some(4)));
+
+		System::set_block_number(3);
+		PoeModule::on_initialize(3);
+
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert!(Proofs::<Test>::contains_key(&kept));
+		assert!(Expiries::<Test>::get(3).is_empty());
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimExpired(1, claim)));
+	})
+}
+
+#[test]
+fn transferred_claims_expire_for_the_new_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), Some(3)));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+
+		System::set_block_number(3);
+		PoeModule::on_initialize(3);
+
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimExpired(2, claim)));
+	})
+}
+
+#[test]
+fn revoke_claim_clears_its_expiry() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), Some(3)));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+
+		assert!(Expiries::<Test>::get(3).is_empty());
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimRevoked(1, claim)));
+	})
+}
+
+#[test]
+fn create_claim_deposit_scales_with_metadata() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		let metadata = ClaimMetadata {
+			mime_type: Some(b"text/plain".to_vec()),
+			description: Some(b"notes".to_vec()),
+		};
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, metadata, None));
+
+		// 10 plus 15 bytes of metadata.
+		assert_eq!(Proofs::<Test>::get(&claim).map(|proof| proof.deposit), Some(25));
+		assert_eq!(Balances::reserved_balance(1), 25);
+	})
+}
+
+#[test]
+fn create_claim_failed_when_deposit_cannot_be_reserved() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(3), claim, Default::default(), Some(5)),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+fn revoke_claim_returns_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	})
+}
+
+#[test]
+fn transfer_claim_moves_the_deposit_and_records_history() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
+
+		System::set_block_number(4);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 90);
+		assert_eq!(Balances::reserved_balance(2), 10);
+		assert_eq!(Proofs::<Test>::get(&claim).map(|proof| proof.block_number), Some(4));
+		assert_eq!(History::<Test>::get(&claim), vec![(1, 1)]);
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimTransferred(1, 2, claim)));
+
+		// The deposit now goes back to the new owner.
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim));
+		assert_eq!(Balances::free_balance(2), 110);
+		assert!(History::<Test>::get(&claim).is_empty());
+	})
+}
+
+#[test]
+fn transfer_claim_failed_when_transferring_to_self() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
+
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(1), claim, 1),
+			Error::<Test>::TransferToSelf
+		);
+	})
+}
+
+#[test]
+fn history_keeps_the_latest_owners() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
+
+		for (block, (from, to)) in [(1, 2), (2, 1), (1, 2)].iter().enumerate() {
+			System::set_block_number(block as u64 + 2);
+			assert_ok!(PoeModule::transfer_claim(Origin::signed(*from), claim, *to));
+		}
+
+		// The first owner's entry was dropped.
+		assert_eq!(History::<Test>::get(&claim), vec![(2, 2), (1, 3)]);
+	})
+}
+
+#[test]
+fn expired_claims_return_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), Some(3)));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+
+		System::set_block_number(3);
+		PoeModule::on_initialize(3);
+
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 110);
+		assert!(History::<Test>::get(&claim).is_empty());
+	})
+}
+
+#[test]
+fn create_claims_batch_works() {
+	new_test_ext().execute_with(|| {
+		let claims: Vec<_> = (1..=3).map(|i| (H256::repeat_byte(i), ClaimMetadata::default())).collect();
+
+		assert_ok!(PoeModule::create_claims_batch(Origin::signed(1), claims, None));
+
+		for i in 1..=3 {
+			assert_eq!(Proofs::<Test>::get(H256::repeat_byte(i)).map(|proof| proof.owner), Some(1));
+		}
+		assert_eq!(Balances::reserved_balance(1), 30);
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::ClaimCreated(1, H256::repeat_byte(3))));
+	})
+}
+
+#[test]
+fn create_claims_batch_weighs_as_much_as_its_claims() {
+	let single = Call::<Test>::create_claim(H256::repeat_byte(1), Default::default(), Some(5)).get_dispatch_info();
+	let claims: Vec<_> = (1..=3).map(|i| (H256::repeat_byte(i), ClaimMetadata::default())).collect();
+	let batch = Call::<Test>::create_claims_batch(claims, Some(5)).get_dispatch_info();
+
+	assert!(single.weight > 0);
+	assert_eq!(batch.weight, 3 * single.weight);
+}
+
+#[test]
+fn create_claims_batch_failed_when_size_is_invalid() {
+	new_test_ext().execute_with(|| {
+		let claims: Vec<_> = (1..=4).map(|i| (H256::repeat_byte(i), ClaimMetadata::default())).collect();
+
+		assert_noop!(
+			PoeModule::create_claims_batch(Origin::signed(1), claims, None),
+			Error::<Test>::InvalidBatchSize
+		);
+		assert_noop!(
+			PoeModule::create_claims_batch(Origin::signed(1), vec![], None),
+			Error::<Test>::InvalidBatchSize
+		);
+	})
+}
+
+#[test]
+fn create_claims_batch_is_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		let claim = H256::repeat_byte(1);
+		let duplicated = vec![(claim, ClaimMetadata::default()), (claim, ClaimMetadata::default())];
+		// Only two claims may expire at a block.
+		let too_many: Vec<_> = (1..=3).map(|i| (H256::repeat_byte(i), ClaimMetadata::default())).collect();
+
+		assert_noop!(
+			PoeModule::create_claims_batch(Origin::signed(1), duplicated, None),
+			Error::<Test>::ProofAlreadyExist
+		);
+		assert_noop!(
+			PoeModule::create_claims_batch(Origin::signed(1), too_many, Some(5)),
+			Error::<Test>::TooManyExpiries
+		);
+	})
+}
+
+#[test]
+fn anchor_merkle_root_works() {
+	new_test_ext().execute_with(|| {
+		let root = H256::repeat_byte(9);
+
+		assert_ok!(PoeModule::anchor_merkle_root(Origin::signed(1), root));
+
+		assert_eq!(MerkleRoots::<Test>::get(&root), Some(Anchor { owner: 1, block_number: 1, deposit: 10 }));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(last_event(), TestEvent::poe(RawEvent::MerkleRootAnchored(1, root)));
+		assert_noop!(
+			PoeModule::anchor_merkle_root(Origin::signed(2), root),
+			Error::<Test>::RootAlreadyAnchored
+		);
+	})
+}
+
+#[test]
+fn revoke_merkle_root_works() {
+	new_test_ext().execute_with(|| {
+		let root = H256::repeat_byte(9);
+		assert_ok!(PoeModule::anchor_merkle_root(Origin::signed(1), root));
+
+		assert_noop!(
+			PoeModule::revoke_merkle_root(Origin::signed(2), root),
+			Error::<Test>::NotRootOwner
+		);
+		assert_ok!(PoeModule::revoke_merkle_root(Origin::signed(1), root));
+
+		assert_eq!(MerkleRoots::<Test>::get(&root), None);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_noop!(
+			PoeModule::revoke_merkle_root(Origin::signed(1), root),
+			Error::<Test>::RootNotExist
+		);
+	})
+}
+
+fn hash_leaf(leaf: H256) -> H256 {
+	BlakeTwo256::hash_of(&(0u8, leaf))
+}
+
+fn hash_pair(a: H256, b: H256) -> H256 {
+	if a <= b { BlakeTwo256::hash_of(&(1u8, a, b)) } else { BlakeTwo256::hash_of(&(1u8, b, a)) }
+}
+
+/// The leaves `0x01..`, `0x02..`, `0x03..`, `0x04..`, their two parents and the root.
+fn merkle_tree() -> (Vec<H256>, H256, H256, H256) {
+	let leaves: Vec<_> = (1..=4).map(H256::repeat_byte).collect();
+	let left = hash_pair(hash_leaf(leaves[0]), hash_leaf(leaves[1]));
+	let right = hash_pair(hash_leaf(leaves[2]), hash_leaf(leaves[3]));
+	(leaves, left, right, hash_pair(left, right))
+}
+
+#[test]
+fn merkle_proofs_are_verified_against_anchored_roots() {
+	new_test_ext().execute_with(|| {
+		let (leaves, left, right, root) = merkle_tree();
+
+		// Not anchored yet.
+		assert!(!PoeModule::verify_merkle_proof(root, leaves[2], vec![hash_leaf(leaves[3]), left]));
+
+		assert_ok!(PoeModule::anchor_merkle_root(Origin::signed(1), root));
+
+		assert!(PoeModule::verify_merkle_proof(root, leaves[2], vec![hash_leaf(leaves[3]), left]));
+		assert!(PoeModule::verify_merkle_proof(root, leaves[1], vec![hash_leaf(leaves[0]), right]));
+		assert!(!PoeModule::verify_merkle_proof(root, H256::repeat_byte(5), vec![hash_leaf(leaves[3]), left]));
+		assert!(!PoeModule::verify_merkle_proof(root, leaves[2], vec![left]));
+	})
+}
+
+#[test]
+fn merkle_proofs_reject_nodes_as_leaves() {
+	new_test_ext().execute_with(|| {
+		let (_, left, right, root) = merkle_tree();
+		assert_ok!(PoeModule::anchor_merkle_root(Origin::signed(1), root));
+
+		// Without the leaf prefix these would prove the parents and the root themselves.
+		assert!(!PoeModule::verify_merkle_proof(root, left, vec![right]));
+		assert!(!PoeModule::verify_merkle_proof(root, root, vec![]));
+	})
+}
+
+#[test]
+fn merkle_root_calls_are_weighed() {
+	let root = H256::repeat_byte(9);
+	assert!(Call::<Test>::anchor_merkle_root(root).get_dispatch_info().weight > 0);
+	assert!(Call::<Test>::revoke_merkle_root(root).get_dispatch_info().weight > 0);
+}
+
+fn put_v1_claim(claim: &[u8], owner: u64) {
+	StorageVersion::put(Releases::V1_0_0);
+	put_storage_value(b"TemplateModule", b"Proofs", &Blake2_128Concat::hash(&claim.to_vec().encode()), (owner, 1u64));
+}
+
+fn v1_claim(claim: &[u8]) -> Option<(u64, u64)> {
+	get_storage_value(b"TemplateModule", b"Proofs", &Blake2_128Concat::hash(&claim.to_vec().encode()))
+}
+
+#[test]
+fn migrate_to_v2_rekeys_v1_claims() {
+	new_test_ext().execute_with(|| {
+		put_v1_claim(b"first", 1);
+		put_v1_claim(b"second", 2);
+		assert_eq!(v1_claim(b"first"), Some((1, 1)));
+
+		let weight = PoeModule::on_runtime_upgrade();
+
+		assert_eq!(weight, RocksDbWeight::get().reads_writes(3, 5));
+		assert_eq!(PoeModule::storage_version(), Releases::V2_0_0);
+		assert_eq!(v1_claim(b"first"), None);
+		assert_eq!(v1_claim(b"second"), None);
+		let first = BlakeTwo256::hash(b"first");
+		assert_eq!(PoeModule::proofs(&first), Some(Proof {
+			owner: 1,
+			block_number: 1,
+			expires: None,
+			metadata: Default::default(),
+			deposit: 0,
+		}));
+		assert_eq!(PoeModule::proofs(BlakeTwo256::hash(b"second")).map(|proof| proof.owner), Some(2));
+
+		// A second upgrade leaves the migrated claims alone, and they work as any other.
+		assert_eq!(PoeModule::on_runtime_upgrade(), RocksDbWeight::get().reads(1));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), first, 2));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), first));
+		assert_eq!(Balances::reserved_balance(2), 0);
+	})
+}
+
+#[test]
+fn new_chains_start_on_the_latest_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(PoeModule::storage_version(), Releases::V2_0_0);
+		let claim = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Default::default(), None));
+
+		// Nothing to migrate, so the upgrade must not drop the claim.
+		assert_eq!(PoeModule::on_runtime_upgrade(), RocksDbWeight::get().reads(1));
+		assert!(Proofs::<Test>::contains_key(&claim));
+	})
+}
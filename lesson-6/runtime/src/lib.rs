@@ -264,8 +264,26 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const ClaimDeposit: Balance = 10_000;
+	pub const DepositPerByte: Balance = 100;
+	pub const MaxHistoryLength: u32 = 32;
+	pub const MaxMimeTypeLength: u32 = 64;
+	pub const MaxDescriptionLength: u32 = 256;
+	pub const MaxExpiriesPerBlock: u32 = 64;
+	pub const MaxBatchSize: u32 = 256;
+}
+
 impl poe::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type ClaimDeposit = ClaimDeposit;
+	type DepositPerByte = DepositPerByte;
+	type MaxHistoryLength = MaxHistoryLength;
+	type MaxMimeTypeLength = MaxMimeTypeLength;
+	type MaxDescriptionLength = MaxDescriptionLength;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -285,7 +303,7 @@ construct_runtime!(
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
-		PoeModule: poe::{Module, Call, Storage, Event<T>},
+		PoeModule: poe::{Module, Call, Storage, Config, Event<T>},
 	}
 );
 
@@ -424,4 +442,10 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	impl poe_runtime_api::PoeApi<Block, Hash> for Runtime {
+		fn verify_merkle_proof(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_merkle_proof(root, leaf, proof)
+		}
+	}
 }